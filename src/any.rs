@@ -1,9 +1,12 @@
-use super::{v0_4, v0_5};
+use super::{
+    v0_4, v0_5,
+    view::{ImageLabelView, MultiscaleImageView, PlateView, WellView},
+};
 use serde::Deserialize;
-use validatrix::Validate;
+use validatrix::{Validate, ValidateContext};
 
 /// OME-Zarr metadata in any supported version.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 #[serde(from = "AnyOmeZarrAttributes")]
 pub enum AnyOmeFields {
     /// Version 0.4 metadata
@@ -25,6 +28,62 @@ impl AnyOmeFields {
             AnyOmeFields::VNext(m) => m.version.to_string(),
         }
     }
+
+    /// Multiscale images, whatever the version; empty if there are none.
+    pub fn multiscales(&self) -> Vec<&dyn MultiscaleImageView> {
+        fn views<T: MultiscaleImageView>(ms: &Option<Vec<T>>) -> Vec<&dyn MultiscaleImageView> {
+            ms.iter()
+                .flatten()
+                .map(|m| m as &dyn MultiscaleImageView)
+                .collect()
+        }
+        match self {
+            AnyOmeFields::V0_4(m) => views(&m.multiscales),
+            AnyOmeFields::V0_5(m) => views(&m.multiscales),
+            #[cfg(feature = "next")]
+            AnyOmeFields::VNext(m) => views(&m.multiscales),
+        }
+    }
+
+    /// Paths of the label images in a labels group.
+    pub fn labels(&self) -> Option<&[String]> {
+        match self {
+            AnyOmeFields::V0_4(m) => m.labels.as_deref(),
+            AnyOmeFields::V0_5(m) => m.labels.as_deref(),
+            #[cfg(feature = "next")]
+            AnyOmeFields::VNext(m) => m.labels.as_deref(),
+        }
+    }
+
+    /// Label image metadata, whatever the version.
+    pub fn image_label(&self) -> Option<&dyn ImageLabelView> {
+        match self {
+            AnyOmeFields::V0_4(m) => m.image_label.as_ref().map(|l| l as _),
+            AnyOmeFields::V0_5(m) => m.image_label.as_ref().map(|l| l as _),
+            #[cfg(feature = "next")]
+            AnyOmeFields::VNext(m) => m.image_label.as_ref().map(|l| l as _),
+        }
+    }
+
+    /// Plate metadata, whatever the version.
+    pub fn plate(&self) -> Option<&dyn PlateView> {
+        match self {
+            AnyOmeFields::V0_4(m) => m.plate.as_ref().map(|p| p as _),
+            AnyOmeFields::V0_5(m) => m.plate.as_ref().map(|p| p as _),
+            #[cfg(feature = "next")]
+            AnyOmeFields::VNext(m) => m.plate.as_ref().map(|p| p as _),
+        }
+    }
+
+    /// Well metadata, whatever the version.
+    pub fn well(&self) -> Option<&dyn WellView> {
+        match self {
+            AnyOmeFields::V0_4(m) => m.well.as_ref().map(|w| w as _),
+            AnyOmeFields::V0_5(m) => m.well.as_ref().map(|w| w as _),
+            #[cfg(feature = "next")]
+            AnyOmeFields::VNext(m) => m.well.as_ref().map(|w| w as _),
+        }
+    }
 }
 
 impl Validate for AnyOmeFields {
@@ -38,6 +97,48 @@ impl Validate for AnyOmeFields {
     }
 }
 
+impl AnyOmeFields {
+    /// Semantic differences from this metadata to `other`,
+    /// after upgrading both to version 0.5 (see [v0_5::diff]).
+    ///
+    /// # Errors
+    /// Returns an error if either is a later version than 0.5.
+    pub fn diff(&self, other: &Self) -> crate::Result<Vec<v0_5::Change>> {
+        fn upgrade(fields: &AnyOmeFields) -> crate::Result<v0_5::OmeFields> {
+            match fields {
+                AnyOmeFields::V0_4(f) => Ok(f.clone().into()),
+                AnyOmeFields::V0_5(f) => Ok(f.clone()),
+                #[cfg(feature = "next")]
+                AnyOmeFields::VNext(f) => Err(crate::Error::general(format!(
+                    "cannot compare version {} metadata",
+                    f.version
+                ))),
+            }
+        }
+        Ok(v0_5::diff(&upgrade(self)?, &upgrade(other)?))
+    }
+}
+
+/// Checks the metadata in the context of the Zarr format version of the group containing it:
+/// version 0.4 metadata must be in a Zarr v2 group, and later versions in a Zarr v3 group.
+impl ValidateContext for AnyOmeFields {
+    type Context = u64;
+
+    fn validate_inner(&self, zarr_format: &Self::Context, accum: &mut validatrix::Accumulator) {
+        Validate::validate_inner(self, accum);
+        let expected = match self {
+            AnyOmeFields::V0_4(_) => v0_4::ZARR_FORMAT,
+            _ => v0_5::ZARR_FORMAT,
+        };
+        if *zarr_format != expected {
+            accum.add_failure(format!(
+                "OME-Zarr version {} requires Zarr format {expected}, got {zarr_format}",
+                self.version()
+            ));
+        }
+    }
+}
+
 /// Extend this enum and related impls when adding support
 /// for future versions.
 #[derive(Debug, Deserialize, Clone)]
@@ -144,11 +245,13 @@ mod tests {
                         name: "y".into(),
                         r#type: Some(v0_4::AxisType::Space),
                         unit: None,
+                        extra: Default::default(),
                     },
                     v0_4::Axis {
                         name: "x".into(),
                         r#type: Some(v0_4::AxisType::Space),
                         unit: None,
+                        extra: Default::default(),
                     },
                 ],
                 datasets: vec![v0_4::MultiscaleImageDataset {
@@ -156,12 +259,15 @@ mod tests {
                     coordinate_transformations: vec![v0_4::CoordinateTransform::Scale(
                         v0_4::CoordinateTransformScale::List {
                             scale: vec![1.0, 1.0],
+                            extra: Default::default(),
                         },
                     )],
+                    extra: Default::default(),
                 }],
                 coordinate_transformations: None,
                 r#type: None,
                 metadata: Default::default(),
+                extra: Default::default(),
             }]),
             ..Default::default()
         };
@@ -180,11 +286,13 @@ mod tests {
                             name: "y".into(),
                             r#type: Some(v0_5::AxisType::Space),
                             unit: None,
+                            extra: Default::default(),
                         },
                         v0_5::Axis {
                             name: "x".into(),
                             r#type: Some(v0_5::AxisType::Space),
                             unit: None,
+                            extra: Default::default(),
                         },
                     ],
                     datasets: vec![v0_5::MultiscaleImageDataset {
@@ -192,17 +300,35 @@ mod tests {
                         coordinate_transformations: vec![v0_5::CoordinateTransform::Scale(
                             v0_5::CoordinateTransformScale::List {
                                 scale: vec![1.0, 1.0],
+                                extra: Default::default(),
                             },
                         )],
+                        extra: Default::default(),
                     }],
                     coordinate_transformations: None,
                     r#type: None,
                     metadata: Default::default(),
+                    extra: Default::default(),
                 }]),
                 ..Default::default()
             },
+            extensions: v0_5::RawExtensions::default(),
         };
         can_roundtrip_specific(&val);
         can_roundtrip_any("0.5", &val);
     }
+
+    #[test]
+    fn zarr_format_matches_version() {
+        let v0_4: AnyOmeFields =
+            serde_json::from_value(serde_json::json!({"labels": ["cells"]})).unwrap();
+        let v0_5: AnyOmeFields = serde_json::from_value(
+            serde_json::json!({"ome": {"version": "0.5", "labels": ["cells"]}}),
+        )
+        .unwrap();
+        ValidateContext::validate(&v0_4, &2).unwrap();
+        ValidateContext::validate(&v0_5, &3).unwrap();
+        assert!(ValidateContext::validate(&v0_4, &3).is_err());
+        assert!(ValidateContext::validate(&v0_5, &2).is_err());
+    }
 }
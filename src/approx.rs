@@ -0,0 +1,348 @@
+#[cfg(feature = "next")]
+use crate::next;
+use crate::{v0_4, v0_5};
+
+/// Tolerance for comparing floating point values with [ApproxEq].
+///
+/// Two values `a` and `b` are approximately equal if they are equal,
+/// or if `|a - b| <= max(absolute, relative * max(|a|, |b|))`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Absolute tolerance, for values close to zero.
+    pub absolute: f64,
+    /// Tolerance relative to the larger magnitude of the values compared.
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    /// Absolute tolerance of `1e-12` and relative tolerance of `1e-9`.
+    fn default() -> Self {
+        Self {
+            absolute: 1e-12,
+            relative: 1e-9,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Tolerance with only an absolute component.
+    pub fn absolute(absolute: f64) -> Self {
+        Self {
+            absolute,
+            relative: 0.0,
+        }
+    }
+
+    /// Tolerance with only a relative component.
+    pub fn relative(relative: f64) -> Self {
+        Self {
+            absolute: 0.0,
+            relative,
+        }
+    }
+
+    /// Whether two floats are within this tolerance of each other.
+    pub fn is_close(&self, a: f64, b: f64) -> bool {
+        // handles infinities
+        a == b || (a - b).abs() <= self.absolute.max(self.relative * a.abs().max(b.abs()))
+    }
+}
+
+/// Equality of metadata up to a [Tolerance] on floating point values.
+///
+/// All other values, including any unknown fields, must be exactly equal.
+pub trait ApproxEq {
+    /// Whether `self` and `other` are equal, with floats compared using the given tolerance.
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool;
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        tolerance.is_close(*self, *other)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for [T] {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other)
+                .all(|(a, b)| a.approx_eq(b, tolerance))
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Vec<T> {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.as_slice().approx_eq(other.as_slice(), tolerance)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Option<T> {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.approx_eq(b, tolerance),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl ApproxEq for v0_4::CoordinateTransform {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        use v0_4::{
+            CoordinateTransform as CT, CoordinateTransformScale as S,
+            CoordinateTransformTranslation as T,
+        };
+        match (self, other) {
+            (
+                CT::Scale(S::List {
+                    scale: a,
+                    extra: extra_a,
+                }),
+                CT::Scale(S::List {
+                    scale: b,
+                    extra: extra_b,
+                }),
+            )
+            | (
+                CT::Translation(T::List {
+                    translation: a,
+                    extra: extra_a,
+                }),
+                CT::Translation(T::List {
+                    translation: b,
+                    extra: extra_b,
+                }),
+            ) => a.approx_eq(b, tolerance) && extra_a == extra_b,
+            _ => self == other,
+        }
+    }
+}
+
+impl ApproxEq for v0_4::MultiscaleImageDataset {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self {
+            path,
+            coordinate_transformations,
+            extra,
+        } = self;
+        *path == other.path
+            && coordinate_transformations.approx_eq(&other.coordinate_transformations, tolerance)
+            && *extra == other.extra
+    }
+}
+
+impl ApproxEq for v0_4::MultiscaleImage {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self {
+            version,
+            name,
+            axes,
+            datasets,
+            coordinate_transformations,
+            r#type,
+            metadata,
+            extra,
+        } = self;
+        *version == other.version
+            && *name == other.name
+            && *axes == other.axes
+            && datasets.approx_eq(&other.datasets, tolerance)
+            && coordinate_transformations.approx_eq(&other.coordinate_transformations, tolerance)
+            && *r#type == other.r#type
+            && *metadata == other.metadata
+            && *extra == other.extra
+    }
+}
+
+impl ApproxEq for v0_5::MultiscaleImage {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self {
+            name,
+            axes,
+            datasets,
+            coordinate_transformations,
+            r#type,
+            metadata,
+            extra,
+        } = self;
+        *name == other.name
+            && *axes == other.axes
+            && datasets.approx_eq(&other.datasets, tolerance)
+            && coordinate_transformations.approx_eq(&other.coordinate_transformations, tolerance)
+            && *r#type == other.r#type
+            && *metadata == other.metadata
+            && *extra == other.extra
+    }
+}
+
+impl ApproxEq for v0_4::Window {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self {
+            min,
+            max,
+            start,
+            end,
+            extra,
+        } = self;
+        min.approx_eq(&other.min, tolerance)
+            && max.approx_eq(&other.max, tolerance)
+            && start.approx_eq(&other.start, tolerance)
+            && end.approx_eq(&other.end, tolerance)
+            && *extra == other.extra
+    }
+}
+
+impl ApproxEq for v0_4::Channel {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self {
+            color,
+            window,
+            other: other_fields,
+        } = self;
+        *color == other.color
+            && window.approx_eq(&other.window, tolerance)
+            && *other_fields == other.other
+    }
+}
+
+impl ApproxEq for v0_4::Omero {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self {
+            channels,
+            other: other_fields,
+        } = self;
+        channels.approx_eq(&other.channels, tolerance) && *other_fields == other.other
+    }
+}
+
+impl ApproxEq for v0_4::OmeNgffGroupAttributes {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self {
+            bioformats2raw,
+            multiscales,
+            labels,
+            image_label,
+            plate,
+            well,
+            omero,
+            extra,
+        } = self;
+        *bioformats2raw == other.bioformats2raw
+            && multiscales.approx_eq(&other.multiscales, tolerance)
+            && *labels == other.labels
+            && *image_label == other.image_label
+            && *plate == other.plate
+            && *well == other.well
+            && omero.approx_eq(&other.omero, tolerance)
+            && *extra == other.extra
+    }
+}
+
+impl ApproxEq for v0_4::OmeNgffGroup {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        let Self { zgroup, attributes } = self;
+        *zgroup == other.zgroup && attributes.approx_eq(&other.attributes, tolerance)
+    }
+}
+
+macro_rules! impl_namespaced_approx_eq {
+    ($version:ident) => {
+        impl ApproxEq for $version::OmeFields {
+            fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+                let Self {
+                    version,
+                    bioformats2raw,
+                    multiscales,
+                    labels,
+                    image_label,
+                    plate,
+                    well,
+                    omero,
+                    extra,
+                } = self;
+                *version == other.version
+                    && *bioformats2raw == other.bioformats2raw
+                    && multiscales.approx_eq(&other.multiscales, tolerance)
+                    && *labels == other.labels
+                    && *image_label == other.image_label
+                    && *plate == other.plate
+                    && *well == other.well
+                    && omero.approx_eq(&other.omero, tolerance)
+                    && *extra == other.extra
+            }
+        }
+
+        impl<E: PartialEq> ApproxEq for $version::OmeZarrGroupAttributes<E> {
+            fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+                let Self { ome, extensions } = self;
+                ome.approx_eq(&other.ome, tolerance) && *extensions == other.extensions
+            }
+        }
+
+        impl<E: PartialEq> ApproxEq for $version::OmeZarrGroupMetadata<E> {
+            fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+                let Self {
+                    zarr_format,
+                    node_type,
+                    attributes,
+                    extra,
+                } = self;
+                *zarr_format == other.zarr_format
+                    && *node_type == other.node_type
+                    && attributes.approx_eq(&other.attributes, tolerance)
+                    && *extra == other.extra
+            }
+        }
+    };
+}
+
+impl_namespaced_approx_eq!(v0_5);
+#[cfg(feature = "next")]
+impl_namespaced_approx_eq!(next);
+
+impl ApproxEq for crate::AnyOmeFields {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        use crate::AnyOmeFields as A;
+        match (self, other) {
+            (A::V0_4(a), A::V0_4(b)) => a.approx_eq(b, tolerance),
+            (A::V0_5(a), A::V0_5(b)) => a.approx_eq(b, tolerance),
+            #[cfg(feature = "next")]
+            (A::VNext(a), A::VNext(b)) => a.approx_eq(b, tolerance),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn multiscale(scale: f64) -> v0_5::MultiscaleImage {
+        serde_json::from_value(json!({
+            "axes": [{"name": "y", "type": "space"}, {"name": "x", "type": "space"}],
+            "datasets": [{"path": "0", "coordinateTransformations": [
+                {"type": "scale", "scale": [scale, scale]}
+            ]}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn tolerance() {
+        let a = multiscale(0.1 + 0.2);
+        let b = multiscale(0.3);
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, &Tolerance::default()));
+        assert!(a.approx_eq(&b, &Tolerance::absolute(1e-15)));
+        assert!(!a.approx_eq(&b, &Tolerance::relative(0.0)));
+        assert!(!a.approx_eq(&multiscale(0.31), &Tolerance::default()));
+
+        let mut c = b.clone();
+        c.name = Some("other".into());
+        assert!(!c.approx_eq(&b, &Tolerance::absolute(1.0)));
+    }
+}
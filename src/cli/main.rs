@@ -0,0 +1,65 @@
+//! CLI for working with OME-Zarr metadata documents.
+#![cfg(feature = "cli")]
+
+use std::{error::Error, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand};
+use ome_zarr_metadata::AnyOmeFields;
+
+/// Command-line arguments for the OME-Zarr metadata CLI
+#[derive(Debug, Parser)]
+#[command(author, version, about = "CLI for working with OME-Zarr metadata.", long_about = None)]
+struct CliArgs {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the semantic differences between two metadata documents, one per line.
+    ///
+    /// Documents may be group attributes (`.zattrs`, or the `attributes` of `zarr.json`)
+    /// or whole `zarr.json` documents; version 0.4 metadata is upgraded to 0.5 before comparison.
+    /// Exits with status 1 if there are any differences.
+    Diff {
+        /// Path to the original metadata document
+        before: PathBuf,
+        /// Path to the changed metadata document
+        after: PathBuf,
+    },
+}
+
+fn read_fields(path: &PathBuf) -> Result<AnyOmeFields, Box<dyn Error>> {
+    let s = std::fs::read_to_string(path)?;
+    let mut value: serde_json::Value = serde_json::from_str(&s)?;
+    if let Some(attributes) = value.get_mut("attributes") {
+        value = attributes.take();
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+fn run(args: CliArgs) -> Result<ExitCode, Box<dyn Error>> {
+    match args.command {
+        Command::Diff { before, after } => {
+            let changes = read_fields(&before)?.diff(&read_fields(&after)?)?;
+            for change in changes.iter() {
+                println!("{change}");
+            }
+            Ok(if changes.is_empty() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(CliArgs::parse()) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
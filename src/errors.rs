@@ -4,11 +4,39 @@ use thiserror::Error;
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Error type wrapping over errors produced in this crate.
+///
+/// Some variants only exist with certain features enabled,
+/// so matches must include a wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Errors from serialising/deserialising JSON.
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
+    /// Errors from reading/writing files.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Errors from reading/writing delimited text.
+    #[cfg(feature = "csv")]
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    /// Errors from parsing XML.
+    #[cfg(feature = "ome-xml")]
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+    /// Errors from applying a JSON Patch.
+    #[cfg(feature = "json-patch")]
+    #[error(transparent)]
+    JsonPatch(#[from] json_patch::PatchError),
+    /// Patched metadata is invalid.
+    #[cfg(feature = "json-patch")]
+    #[error("metadata is invalid from patch operation {operation} onwards")]
+    PatchInvalid {
+        /// Index of the earliest operation after which the metadata stays invalid.
+        operation: usize,
+        /// Deserialisation or validation error for the patched metadata.
+        source: Box<Error>,
+    },
     /// Data fails validation.
     #[error(transparent)]
     Validation(#[from] validatrix::Error),
@@ -26,6 +54,20 @@ pub enum Error {
         /// The version which failed to satisfy the constraint.
         version: pep440_rs::Version,
     },
+    /// Coordinate transformation refers to binary data rather than listing its values.
+    #[error("coordinate transformation at path {0:?} cannot be resolved from metadata alone")]
+    PathTransform(std::path::PathBuf),
+    /// Path refers to a location above the root of a Zarr hierarchy.
+    #[error("path {0:?} is outside the hierarchy")]
+    PathOutsideHierarchy(String),
+    /// Number of dimensions does not match the metadata.
+    #[error("got {actual} dimensions, expected {expected}")]
+    NDimMismatch {
+        /// Number of dimensions according to the metadata.
+        expected: usize,
+        /// Number of dimensions given.
+        actual: usize,
+    },
     /// General error.
     #[error("{0}")]
     General(String),
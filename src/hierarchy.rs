@@ -0,0 +1,461 @@
+use std::collections::BTreeMap;
+
+use validatrix::{Accumulator, Validate, ValidateContext};
+
+use crate::{
+    AnyOmeFields, Error, Result,
+    v0_4::{Bioformats2Raw, bioformats2raw_layout::validate_series_in_hierarchy},
+    v0_5,
+    view::{ImageLabelView, MultiscaleImageView},
+};
+
+/// OME-Zarr metadata of the groups in a Zarr hierarchy,
+/// keyed by their `/`-separated path relative to the root of the hierarchy.
+///
+/// Used for validating references between groups, such as a label image's source image.
+/// Paths are normalised on insertion; the root group has the empty path `""`.
+/// The Zarr format version of each group may also be recorded, for checking it against the metadata.
+#[derive(Debug, Clone)]
+pub struct Hierarchy<T> {
+    groups: BTreeMap<String, T>,
+    zarr_formats: BTreeMap<String, u64>,
+}
+
+impl<T> Default for Hierarchy<T> {
+    fn default() -> Self {
+        Self {
+            groups: BTreeMap::default(),
+            zarr_formats: BTreeMap::default(),
+        }
+    }
+}
+
+impl<T> Hierarchy<T> {
+    /// Build a hierarchy from groups' paths and metadata; see [Hierarchy::insert].
+    ///
+    /// # Errors
+    /// Returns [Error::PathOutsideHierarchy] for the first path outside the hierarchy.
+    pub fn try_from_iter<I: IntoIterator<Item = (String, T)>>(iter: I) -> Result<Self> {
+        let mut out = Self::default();
+        for (path, metadata) in iter {
+            out.insert(&path, metadata)?;
+        }
+        Ok(out)
+    }
+
+    /// Add a group's metadata at the given path, returning any metadata previously at that path.
+    ///
+    /// # Errors
+    /// Returns [Error::PathOutsideHierarchy] if the path refers to a location above the root
+    /// (e.g. `"../x"`), in which case the hierarchy is unchanged.
+    pub fn insert(&mut self, path: &str, metadata: T) -> Result<Option<T>> {
+        let resolved =
+            resolve_path("", path).ok_or_else(|| Error::PathOutsideHierarchy(path.to_string()))?;
+        self.zarr_formats.remove(&resolved);
+        Ok(self.groups.insert(resolved, metadata))
+    }
+
+    /// Add a group's metadata at the given path along with the Zarr format version of the group,
+    /// as in its `.zgroup` or `zarr.json`.
+    ///
+    /// # Errors
+    /// As [Hierarchy::insert].
+    pub fn insert_with_zarr_format(
+        &mut self,
+        path: &str,
+        zarr_format: u64,
+        metadata: T,
+    ) -> Result<Option<T>> {
+        let previous = self.insert(path, metadata)?;
+        if let Some(resolved) = resolve_path("", path) {
+            self.zarr_formats.insert(resolved, zarr_format);
+        }
+        Ok(previous)
+    }
+
+    /// Get the metadata of the group at the given path.
+    pub fn get(&self, path: &str) -> Option<&T> {
+        self.groups.get(&resolve_path("", path)?)
+    }
+
+    /// Get the Zarr format version of the group at the given path, if it was recorded.
+    pub fn zarr_format(&self, path: &str) -> Option<u64> {
+        self.zarr_formats.get(&resolve_path("", path)?).copied()
+    }
+
+    /// Iterate over the normalised paths and metadata of all groups, in path order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.groups.iter().map(|(p, m)| (p.as_str(), m))
+    }
+
+    /// Number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Whether there are no groups.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Get the group at the given path along with this hierarchy, for validation.
+    pub fn group(&self, path: &str) -> Option<HierarchyGroup<'_, T>> {
+        let (path, metadata) = self.groups.get_key_value(&resolve_path("", path)?)?;
+        Some(HierarchyGroup {
+            hierarchy: self,
+            path,
+            metadata,
+            zarr_format: self.zarr_formats.get(path).copied(),
+        })
+    }
+
+    /// Validate every group in the hierarchy, returning the failures for each invalid group by path.
+    pub fn validate_groups(&self) -> BTreeMap<String, validatrix::Error>
+    where
+        for<'a> HierarchyGroup<'a, T>: Validate,
+    {
+        self.groups
+            .keys()
+            .filter_map(|path| {
+                let err = self.group(path)?.validate().err()?;
+                Some((path.clone(), err))
+            })
+            .collect()
+    }
+}
+
+/// Resolve a `/`-separated path relative to the group at `base`.
+///
+/// Returns `None` if the path refers to a location above the root of the hierarchy.
+/// Leading slashes are ignored; paths are always relative.
+pub fn resolve_path(base: &str, relative: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::default();
+    for part in base.split('/').chain(relative.split('/')) {
+        match part {
+            "" | "." => (),
+            ".." => {
+                parts.pop()?;
+            }
+            p => parts.push(p),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Split a normalised path into the path of its parent group and its own name.
+///
+/// Returns `None` for the root group.
+pub(crate) fn split_parent(path: &str) -> Option<(&str, &str)> {
+    if path.is_empty() {
+        return None;
+    }
+    Some(path.rsplit_once('/').unwrap_or(("", path)))
+}
+
+/// A group's metadata, in the context of the [Hierarchy] which contains it.
+///
+/// Validating this validates the group's own metadata,
+/// as well as its references to other groups in the hierarchy.
+#[derive(Debug, Clone, Copy)]
+pub struct HierarchyGroup<'a, T> {
+    /// The hierarchy containing the group.
+    pub hierarchy: &'a Hierarchy<T>,
+    /// Normalised path of the group within the hierarchy.
+    pub path: &'a str,
+    /// Metadata of the group.
+    pub metadata: &'a T,
+    /// Zarr format version of the group, if it was recorded.
+    pub zarr_format: Option<u64>,
+}
+
+impl<T> HierarchyGroup<'_, T> {
+    /// Resolve a path relative to this group, and get the metadata there.
+    ///
+    /// Returns the resolved path, and the metadata if the group exists.
+    pub fn relative(&self, relative: &str) -> Option<(String, Option<&T>)> {
+        let path = resolve_path(self.path, relative)?;
+        let metadata = self.hierarchy.groups.get(&path);
+        Some((path, metadata))
+    }
+}
+
+impl Validate for HierarchyGroup<'_, v0_5::OmeFields> {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        self.metadata.validate_inner(accum);
+        validate_references(accum, self);
+    }
+}
+
+/// Checks each group's metadata, and that its OME-Zarr version matches its Zarr format if recorded
+/// (see [Hierarchy::insert_with_zarr_format]).
+impl Validate for HierarchyGroup<'_, AnyOmeFields> {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        match self.zarr_format {
+            Some(zarr_format) => {
+                ValidateContext::validate_inner(self.metadata, &zarr_format, accum)
+            }
+            None => Validate::validate_inner(self.metadata, accum),
+        }
+        validate_references(accum, self);
+    }
+}
+
+/// Version-independent access to the metadata which refers to other groups in a hierarchy.
+pub(crate) trait GroupReferences {
+    /// The first multiscale image, if any.
+    fn multiscale(&self) -> Option<&dyn MultiscaleImageView>;
+
+    /// Paths of the label images in a labels group.
+    fn labels(&self) -> Option<&[String]>;
+
+    /// Label image metadata.
+    fn image_label(&self) -> Option<&dyn ImageLabelView>;
+
+    /// bioformats2raw layout metadata.
+    fn bioformats2raw(&self) -> Option<&Bioformats2Raw>;
+
+    /// Whether the group describes a plate.
+    fn has_plate(&self) -> bool;
+}
+
+impl GroupReferences for v0_5::OmeFields {
+    fn multiscale(&self) -> Option<&dyn MultiscaleImageView> {
+        self.multiscales.as_ref()?.first().map(|m| m as _)
+    }
+
+    fn labels(&self) -> Option<&[String]> {
+        self.labels.as_deref()
+    }
+
+    fn image_label(&self) -> Option<&dyn ImageLabelView> {
+        self.image_label.as_ref().map(|l| l as _)
+    }
+
+    fn bioformats2raw(&self) -> Option<&Bioformats2Raw> {
+        self.bioformats2raw.as_ref()
+    }
+
+    fn has_plate(&self) -> bool {
+        self.plate.is_some()
+    }
+}
+
+impl GroupReferences for AnyOmeFields {
+    fn multiscale(&self) -> Option<&dyn MultiscaleImageView> {
+        AnyOmeFields::multiscales(self).first().copied()
+    }
+
+    fn labels(&self) -> Option<&[String]> {
+        AnyOmeFields::labels(self)
+    }
+
+    fn image_label(&self) -> Option<&dyn ImageLabelView> {
+        AnyOmeFields::image_label(self)
+    }
+
+    fn bioformats2raw(&self) -> Option<&Bioformats2Raw> {
+        match self {
+            AnyOmeFields::V0_4(m) => m.bioformats2raw.as_ref(),
+            AnyOmeFields::V0_5(m) => m.bioformats2raw.as_ref(),
+            #[cfg(feature = "next")]
+            AnyOmeFields::VNext(m) => m.bioformats2raw.as_ref(),
+        }
+    }
+
+    fn has_plate(&self) -> bool {
+        self.plate().is_some()
+    }
+}
+
+/// Check a group's references to other groups: a label image's source image and parent,
+/// and a bioformats2raw layout's series.
+fn validate_references<T: GroupReferences>(accum: &mut Accumulator, group: &HierarchyGroup<'_, T>) {
+    if let Some(label) = group.metadata.image_label() {
+        accum.with_key("imageLabel", |a| {
+            v0_5::validate_label_in_hierarchy(a, group, label);
+        });
+    }
+
+    if let Some(layout) = group.metadata.bioformats2raw() {
+        validate_series_in_hierarchy(accum, layout, group, group.metadata.has_plate(), |m| {
+            m.multiscale().is_some()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        assert_eq!(
+            resolve_path("img/labels/seg", "../../").as_deref(),
+            Some("img")
+        );
+        assert_eq!(resolve_path("a", "./b/../c").as_deref(), Some("a/c"));
+        assert_eq!(resolve_path("a", "../..").as_deref(), None);
+        assert_eq!(split_parent("img/labels"), Some(("img", "labels")));
+        assert_eq!(split_parent("img"), Some(("", "img")));
+        assert_eq!(split_parent(""), None);
+    }
+
+    #[test]
+    fn insert_outside_root() {
+        let mut hierarchy = Hierarchy::default();
+        assert!(hierarchy.insert("", 0).unwrap().is_none());
+        for path in ["../x", "a/../.."] {
+            assert!(matches!(
+                hierarchy.insert(path, 1),
+                Err(Error::PathOutsideHierarchy(_))
+            ));
+        }
+        assert_eq!(hierarchy.get(""), Some(&0));
+        assert_eq!(hierarchy.len(), 1);
+        assert_eq!(hierarchy.insert("a/../b", 2).unwrap(), None);
+        assert_eq!(hierarchy.get("b"), Some(&2));
+    }
+
+    #[test]
+    fn from_iter_outside_root() {
+        let result = Hierarchy::try_from_iter([("a".to_string(), 0), ("../x".to_string(), 1)]);
+        assert!(matches!(result, Err(Error::PathOutsideHierarchy(p)) if p == "../x"));
+        let hierarchy = Hierarchy::try_from_iter([("a/./b".to_string(), 0)]).unwrap();
+        assert_eq!(hierarchy.get("a/b"), Some(&0));
+    }
+
+    #[test]
+    fn zarr_format_per_group() {
+        let any =
+            |value: serde_json::Value| -> AnyOmeFields { serde_json::from_value(value).unwrap() };
+        let mut hierarchy = Hierarchy::default();
+        hierarchy
+            .insert_with_zarr_format("v2", 2, any(serde_json::json!({"labels": ["a"]})))
+            .unwrap();
+        hierarchy
+            .insert_with_zarr_format(
+                "v3",
+                3,
+                any(serde_json::json!({"ome": {"version": "0.5", "labels": ["a"]}})),
+            )
+            .unwrap();
+        hierarchy
+            .insert("unknown", any(serde_json::json!({"labels": ["a"]})))
+            .unwrap();
+        assert_eq!(hierarchy.zarr_format("v2"), Some(2));
+        assert_eq!(hierarchy.zarr_format("unknown"), None);
+        assert!(hierarchy.validate_groups().is_empty());
+
+        hierarchy
+            .insert_with_zarr_format("v3", 2, any(serde_json::json!({"ome": {"version": "0.5"}})))
+            .unwrap();
+        let errors = hierarchy.validate_groups();
+        assert_eq!(errors.keys().collect::<Vec<_>>(), ["v3"]);
+
+        // re-inserting without a format forgets the previous one
+        hierarchy
+            .insert("v3", any(serde_json::json!({"ome": {"version": "0.5"}})))
+            .unwrap();
+        assert_eq!(hierarchy.zarr_format("v3"), None);
+        assert!(hierarchy.validate_groups().is_empty());
+    }
+
+    fn fields(value: serde_json::Value) -> v0_5::OmeFields {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn image() -> v0_5::OmeFields {
+        fields(serde_json::json!({
+            "version": "0.5",
+            "multiscales": [{
+                "axes": [{"name": "y", "type": "space"}, {"name": "x", "type": "space"}],
+                "datasets": [{"path": "0", "coordinateTransformations": [
+                    {"type": "scale", "scale": [1.0, 1.0]}
+                ]}]
+            }]
+        }))
+    }
+
+    #[test]
+    fn bioformats2raw_series() {
+        let root = |series: Option<&[&str]>| {
+            let mut value = serde_json::json!({"version": "0.5", "bioformats2raw.layout": 3});
+            if let Some(s) = series {
+                value["series"] = serde_json::json!(s);
+            }
+            fields(value)
+        };
+
+        let mut hierarchy = Hierarchy::default();
+        hierarchy.insert("", root(None)).unwrap();
+        hierarchy.insert("0", image()).unwrap();
+        hierarchy.insert("1", image()).unwrap();
+        hierarchy
+            .insert("OME", fields(serde_json::json!({"version": "0.5"})))
+            .unwrap();
+        let group = hierarchy.group("").unwrap();
+        let layout = group.metadata.bioformats2raw.as_ref().unwrap();
+        assert_eq!(layout.series_paths(&group).collect::<Vec<_>>(), ["0", "1"]);
+        assert!(hierarchy.validate_groups().is_empty());
+
+        hierarchy
+            .insert("", root(Some(&["1", "OME", "2"])))
+            .unwrap();
+        let errors = hierarchy.validate_groups();
+        // "OME" is not an image, and "2" does not exist
+        assert_eq!(errors[""].len(), 2);
+
+        hierarchy.insert("", root(None)).unwrap();
+        hierarchy
+            .insert("0", fields(serde_json::json!({"version": "0.5"})))
+            .unwrap();
+        assert_eq!(hierarchy.validate_groups()[""].len(), 1);
+    }
+
+    #[test]
+    fn any_version_references() {
+        let any =
+            |value: serde_json::Value| -> AnyOmeFields { serde_json::from_value(value).unwrap() };
+        let multiscales = serde_json::json!([{
+            "version": "0.4",
+            "axes": [{"name": "y", "type": "space"}, {"name": "x", "type": "space"}],
+            "datasets": [{"path": "0", "coordinateTransformations": [
+                {"type": "scale", "scale": [1.0, 1.0]}
+            ]}]
+        }]);
+        let label = |source: &str| {
+            any(serde_json::json!({
+                "multiscales": multiscales,
+                "image-label": {"version": "0.4", "source": {"image": source}}
+            }))
+        };
+
+        let mut hierarchy = Hierarchy::default();
+        hierarchy
+            .insert(
+                "",
+                any(serde_json::json!({"bioformats2raw.layout": 3, "series": ["img"]})),
+            )
+            .unwrap();
+        hierarchy
+            .insert("img", any(serde_json::json!({"multiscales": multiscales})))
+            .unwrap();
+        hierarchy
+            .insert("img/labels", any(serde_json::json!({"labels": ["seg"]})))
+            .unwrap();
+        hierarchy.insert("img/labels/seg", label("../../")).unwrap();
+        assert!(hierarchy.validate_groups().is_empty());
+
+        hierarchy
+            .insert("img/labels/seg", label("../../missing"))
+            .unwrap();
+        hierarchy
+            .insert(
+                "",
+                any(serde_json::json!({"bioformats2raw.layout": 3, "series": ["img/labels"]})),
+            )
+            .unwrap();
+        let errors = hierarchy.validate_groups();
+        assert_eq!(errors.keys().collect::<Vec<_>>(), ["", "img/labels/seg"]);
+    }
+}
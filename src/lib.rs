@@ -4,6 +4,8 @@
 #![deny(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "json-patch")]
+pub use json_patch;
 pub use pep440_rs;
 
 /// Utilities for working with OME-Zarr version strings.
@@ -27,13 +29,46 @@ pub mod v0_5;
 #[cfg(feature = "next")]
 pub mod next;
 
+/// Typed subset of the OME-XML companion metadata written by bioformats2raw.
+///
+/// <https://ngff.openmicroscopy.org/0.5/#bf2raw>.
+/// <https://www.openmicroscopy.org/Schemas/Documentation/Generated/OME-2016-06/ome.html>.
+#[cfg(feature = "ome-xml")]
+pub mod ome_xml;
+
+/// Export of OME-Zarr metadata as [Neuroglancer](https://github.com/google/neuroglancer) viewer-state layers.
+///
+/// Layers refer to their data by a Neuroglancer data source URL,
+/// e.g. `"zarr3://https://example.com/image.zarr"`, which is passed through unchanged.
+pub mod neuroglancer;
+
+/// Export of OME-Zarr metadata as [napari](https://napari.org) layer keyword arguments.
+///
+/// These are JSON objects of the keyword arguments to napari's `add_image` and `add_labels`,
+/// so that scripts can load the metadata and pass it straight to napari.
+pub mod napari;
+
 mod errors;
 pub use errors::{Error, Result};
 
 mod ndim;
 pub use ndim::{MaybeNDim, NDim};
 
+mod xml;
+
 pub use validatrix::{Valid, Validate};
 
 mod any;
 pub use any::AnyOmeFields;
+
+mod view;
+pub use view::{ImageLabelView, MultiscaleImageView, PlateView, WellView};
+
+mod approx;
+pub use approx::{ApproxEq, Tolerance};
+
+mod hierarchy;
+pub use hierarchy::{Hierarchy, HierarchyGroup, resolve_path};
+
+mod strict;
+pub use strict::{Strict, UnknownFields};
@@ -0,0 +1,287 @@
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Error, Result,
+    v0_5::{AxisType, ImageLabel, MultiscaleImage, OmeFields, Omero},
+};
+
+/// Unit used by napari for axes without a physical unit.
+const PIXEL_UNIT: &str = "pixel";
+
+/// Keyword arguments shared by image and labels layers:
+/// `name`, `scale`, `translate`, `axis_labels`, `units`, and `multiscale`,
+/// omitting the axis at `skip_axis`.
+fn spatial_kwargs(
+    multiscale: &MultiscaleImage,
+    skip_axis: Option<usize>,
+) -> Result<Map<String, Value>> {
+    let base = multiscale
+        .level_transforms()?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::general("multiscale image has no datasets"))?;
+    let keep = |idx: &usize| Some(*idx) != skip_axis;
+    let pick = |values: &[f64]| -> Vec<f64> {
+        values
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, v)| keep(&idx).then_some(*v))
+            .collect()
+    };
+    let axes: Vec<_> = multiscale
+        .axes
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, a)| keep(&idx).then_some(a))
+        .collect();
+
+    let mut kwargs = Map::default();
+    if let Some(name) = multiscale.name.as_ref() {
+        kwargs.insert("name".into(), name.clone().into());
+    }
+    kwargs.insert("scale".into(), json!(pick(&base.scale)));
+    kwargs.insert("translate".into(), json!(pick(&base.translation)));
+    kwargs.insert(
+        "axis_labels".into(),
+        axes.iter().map(|a| a.name.clone()).collect(),
+    );
+    kwargs.insert(
+        "units".into(),
+        axes.iter()
+            .map(|a| match a.unit.as_ref() {
+                Some(u) => serde_json::to_value(u).unwrap_or_else(|_| PIXEL_UNIT.into()),
+                None => PIXEL_UNIT.into(),
+            })
+            .collect(),
+    );
+    kwargs.insert("multiscale".into(), (multiscale.datasets.len() > 1).into());
+    Ok(kwargs)
+}
+
+/// Linear colormap from black to the given color, as accepted by napari.
+fn colormap(r: u8, g: u8, b: u8) -> Value {
+    let c = |v: u8| f64::from(v) / 255.0;
+    json!({
+        "name": format!("#{r:02x}{g:02x}{b:02x}"),
+        "colors": [[0.0, 0.0, 0.0, 1.0], [c(r), c(g), c(b), 1.0]],
+    })
+}
+
+/// Keyword arguments for napari's `add_image`.
+///
+/// Spatial arguments (`scale`, `translate`, `axis_labels`, `units`) come from the axes
+/// and the full-resolution transformations;
+/// axes without a unit have the unit `"pixel"`.
+///
+/// With `omero` metadata, `contrast_limits`, `colormap`, `blending`, `visible`,
+/// and `name` come from the channels' `window`, `color`, `active`, and `label` fields.
+/// If the image has a channel axis, it is given as `channel_axis`
+/// and excluded from the spatial arguments, and the per-channel arguments are lists.
+///
+/// # Errors
+/// Returns an error if `omero` has several channels but the image has no channel axis,
+/// or if the transformations cannot be resolved (see [MultiscaleImage::level_transforms]).
+pub fn image_kwargs(
+    multiscale: &MultiscaleImage,
+    omero: Option<&Omero>,
+) -> Result<Map<String, Value>> {
+    let channel_axis = multiscale
+        .axes
+        .iter()
+        .position(|a| a.r#type == Some(AxisType::Channel));
+    let mut kwargs = spatial_kwargs(multiscale, channel_axis)?;
+    let Some(omero) = omero else {
+        if let Some(idx) = channel_axis {
+            kwargs.insert("channel_axis".into(), idx.into());
+        }
+        return Ok(kwargs);
+    };
+    if channel_axis.is_none() && omero.channels.len() > 1 {
+        return Err(Error::general(format!(
+            "got {} omero channels for an image with no channel axis",
+            omero.channels.len()
+        )));
+    }
+
+    let mut names = Vec::default();
+    let mut contrast_limits = Vec::default();
+    let mut colormaps = Vec::default();
+    let mut visible = Vec::default();
+    for (idx, ch) in omero.channels.iter().enumerate() {
+        let name = ch.other.get("label").and_then(Value::as_str).map_or_else(
+            || match multiscale.name.as_ref() {
+                Some(n) => format!("{n} channel {idx}"),
+                None => format!("channel {idx}"),
+            },
+            String::from,
+        );
+        names.push(Value::from(name));
+        contrast_limits.push(json!([ch.window.start, ch.window.end]));
+        colormaps.push(colormap(ch.color.r, ch.color.g, ch.color.b));
+        visible.push(
+            ch.other
+                .get("active")
+                .and_then(Value::as_bool)
+                .unwrap_or(true)
+                .into(),
+        );
+    }
+
+    let blending = if omero.channels.len() > 1 {
+        "additive"
+    } else {
+        "translucent"
+    };
+    kwargs.insert("blending".into(), blending.into());
+    match channel_axis {
+        Some(idx) => {
+            kwargs.insert("channel_axis".into(), idx.into());
+            kwargs.insert("name".into(), names.into());
+            kwargs.insert("contrast_limits".into(), contrast_limits.into());
+            kwargs.insert("colormap".into(), colormaps.into());
+            kwargs.insert("visible".into(), visible.into());
+        }
+        None => {
+            for (key, mut values) in [
+                ("name", names),
+                ("contrast_limits", contrast_limits),
+                ("colormap", colormaps),
+                ("visible", visible),
+            ] {
+                if let Some(v) = values.pop() {
+                    kwargs.insert(key.into(), v);
+                }
+            }
+        }
+    }
+    Ok(kwargs)
+}
+
+/// Keyword arguments for napari's `add_labels`.
+///
+/// Spatial arguments are as for [image_kwargs].
+/// If the label image lists `colors`, they are given as a `colormap`
+/// mapping label values (as strings, since JSON keys must be strings) to RGBA in `[0, 1]`.
+///
+/// # Errors
+/// Returns an error if the transformations cannot be resolved
+/// (see [MultiscaleImage::level_transforms]).
+pub fn labels_kwargs(
+    multiscale: &MultiscaleImage,
+    label: &ImageLabel,
+) -> Result<Map<String, Value>> {
+    let mut kwargs = spatial_kwargs(multiscale, None)?;
+    if let Some(colors) = label.colors.as_ref() {
+        let colormap: Map<String, Value> = colors
+            .iter()
+            .map(|c| {
+                let rgba = c.rgba.map(|v| f64::from(v) / 255.0);
+                (c.label_value.to_string(), json!(rgba))
+            })
+            .collect();
+        kwargs.insert("colormap".into(), colormap.into());
+    }
+    Ok(kwargs)
+}
+
+/// napari layer keyword arguments for each multiscale image in a group.
+///
+/// If the group is a label image, these are arguments for `add_labels` (see [labels_kwargs]);
+/// otherwise, they are for `add_image` (see [image_kwargs]).
+///
+/// # Errors
+/// See [image_kwargs] and [labels_kwargs].
+pub fn layer_kwargs(fields: &OmeFields) -> Result<Vec<Map<String, Value>>> {
+    fields
+        .multiscales
+        .iter()
+        .flatten()
+        .map(|ms| match fields.image_label.as_ref() {
+            Some(label) => labels_kwargs(ms, label),
+            None => image_kwargs(ms, fields.omero.as_ref()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(extra: Value) -> OmeFields {
+        let mut value = json!({
+            "version": "0.5",
+            "multiscales": [{
+                "name": "cells",
+                "axes": [
+                    {"name": "c", "type": "channel"},
+                    {"name": "y", "type": "space", "unit": "micrometer"},
+                    {"name": "x", "type": "space"}
+                ],
+                "datasets": [
+                    {"path": "0", "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 0.5, 0.5]},
+                        {"type": "translation", "translation": [0.0, 10.0, 20.0]}
+                    ]},
+                    {"path": "1", "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 1.0, 1.0]}
+                    ]}
+                ]
+            }]
+        });
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn image_kwargs_snapshot() {
+        let f = fields(json!({"omero": {"channels": [
+            {"color": "FF0000", "label": "red", "active": false,
+             "window": {"min": 0.0, "max": 255.0, "start": 10.0, "end": 200.0}},
+            {"color": "00FF00",
+             "window": {"min": 0.0, "max": 255.0, "start": 0.0, "end": 255.0}}
+        ]}}));
+        let kwargs = layer_kwargs(&f).unwrap();
+        assert_eq!(
+            Value::from(kwargs[0].clone()),
+            json!({
+                "name": ["red", "cells channel 1"],
+                "scale": [0.5, 0.5],
+                "translate": [10.0, 20.0],
+                "axis_labels": ["y", "x"],
+                "units": ["micrometer", "pixel"],
+                "multiscale": true,
+                "blending": "additive",
+                "channel_axis": 0,
+                "contrast_limits": [[10.0, 200.0], [0.0, 255.0]],
+                "colormap": [
+                    {"name": "#ff0000", "colors": [[0.0, 0.0, 0.0, 1.0], [1.0, 0.0, 0.0, 1.0]]},
+                    {"name": "#00ff00", "colors": [[0.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]]}
+                ],
+                "visible": [false, true],
+            })
+        );
+    }
+
+    #[test]
+    fn labels_kwargs_snapshot() {
+        let f = fields(json!({"image-label": {"colors": [
+            {"label-value": 1, "rgba": [255, 0, 0, 255]}
+        ]}}));
+        let kwargs = layer_kwargs(&f).unwrap();
+        assert_eq!(
+            Value::from(kwargs[0].clone()),
+            json!({
+                "name": "cells",
+                "scale": [1.0, 0.5, 0.5],
+                "translate": [0.0, 10.0, 20.0],
+                "axis_labels": ["c", "y", "x"],
+                "units": ["pixel", "micrometer", "pixel"],
+                "multiscale": true,
+                "colormap": {"1": [1.0, 0.0, 0.0, 1.0]},
+            })
+        );
+    }
+}
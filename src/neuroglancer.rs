@@ -0,0 +1,267 @@
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Error, Result,
+    v0_5::{AxisType, AxisUnit, ImageLabel, MultiscaleImage, Omero},
+};
+
+/// Name suffix which makes an output dimension local to a layer in Neuroglancer.
+const LOCAL_SUFFIX: char = '\'';
+
+/// Shader for a single channel, with contrast and color controls.
+const CHANNEL_SHADER: &str = "#uicontrol invlerp contrast
+#uicontrol vec3 color color
+void main() {
+  emitRGB(color * contrast());
+}
+";
+
+/// Neuroglancer output dimensions for a multiscale image, keyed by axis name,
+/// as `[voxel size, unit]` from the full-resolution scale.
+///
+/// Space and time units are converted to meters and seconds, as Neuroglancer expects.
+/// Axes without a recognised unit are unitless;
+/// channel axes are made local to the layer (their names are suffixed with `'`).
+///
+/// # Errors
+/// Returns an error if the multiscale image has no datasets,
+/// or if the transformations cannot be resolved (see [MultiscaleImage::level_transforms]).
+pub fn output_dimensions(multiscale: &MultiscaleImage) -> Result<Map<String, Value>> {
+    let scale = multiscale
+        .level_transforms()?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::general("multiscale image has no datasets"))?
+        .scale;
+    Ok(multiscale
+        .axes
+        .iter()
+        .zip(scale)
+        .map(|(axis, scale)| {
+            let (name, dim) = match (&axis.r#type, &axis.unit) {
+                (Some(AxisType::Channel), _) => {
+                    (format!("{}{LOCAL_SUFFIX}", axis.name), json!([1, ""]))
+                }
+                (_, Some(AxisUnit::Space(u))) => {
+                    (axis.name.clone(), json!([scale * u.in_meters(), "m"]))
+                }
+                (_, Some(AxisUnit::Time(u))) => {
+                    (axis.name.clone(), json!([scale * u.in_seconds(), "s"]))
+                }
+                _ => (axis.name.clone(), json!([scale, ""])),
+            };
+            (name, dim)
+        })
+        .collect())
+}
+
+fn source(url: &str, multiscale: &MultiscaleImage) -> Result<Value> {
+    Ok(json!({
+        "url": url,
+        "transform": {"outputDimensions": output_dimensions(multiscale)?},
+    }))
+}
+
+/// Neuroglancer image layers for a multiscale image.
+///
+/// With `omero` metadata, there is one layer per channel,
+/// with shader controls for the channel's `window` and `color`,
+/// additive blending, and visibility from its `active` field.
+/// Without it, there is a single layer with Neuroglancer's default shader.
+///
+/// # Errors
+/// Returns an error if `omero` has several channels but the image has no channel axis,
+/// or see [output_dimensions].
+pub fn image_layers(
+    url: &str,
+    multiscale: &MultiscaleImage,
+    omero: Option<&Omero>,
+) -> Result<Vec<Value>> {
+    let name = multiscale.name.as_deref().unwrap_or("image");
+    let source = source(url, multiscale)?;
+    let Some(omero) = omero else {
+        return Ok(vec![json!({
+            "type": "image",
+            "name": name,
+            "source": source,
+        })]);
+    };
+
+    let has_channel_axis = multiscale
+        .axes
+        .iter()
+        .any(|a| a.r#type == Some(AxisType::Channel));
+    if !has_channel_axis && omero.channels.len() > 1 {
+        return Err(Error::general(format!(
+            "got {} omero channels for an image with no channel axis",
+            omero.channels.len()
+        )));
+    }
+
+    Ok(omero
+        .channels
+        .iter()
+        .enumerate()
+        .map(|(idx, ch)| {
+            let label = ch
+                .other
+                .get("label")
+                .and_then(Value::as_str)
+                .map_or_else(|| format!("{name} channel {idx}"), String::from);
+            let visible = ch
+                .other
+                .get("active")
+                .and_then(Value::as_bool)
+                .unwrap_or(true);
+            let mut layer = json!({
+                "type": "image",
+                "name": label,
+                "source": source,
+                "visible": visible,
+                "blend": "additive",
+                "shader": CHANNEL_SHADER,
+                "shaderControls": {
+                    "contrast": {
+                        "range": [ch.window.start, ch.window.end],
+                        "window": [ch.window.min, ch.window.max],
+                    },
+                    "color": format!("#{:02x}{:02x}{:02x}", ch.color.r, ch.color.g, ch.color.b),
+                },
+            });
+            if has_channel_axis {
+                // centre of the channel's voxel
+                layer["localPosition"] = json!([idx as f64 + 0.5]);
+            }
+            layer
+        })
+        .collect())
+}
+
+/// Neuroglancer segmentation layer for a label image, with segment colors from its `colors`.
+///
+/// # Errors
+/// See [output_dimensions].
+pub fn segmentation_layer(
+    url: &str,
+    multiscale: &MultiscaleImage,
+    label: &ImageLabel,
+) -> Result<Value> {
+    let colors: Map<String, Value> = label
+        .colors
+        .iter()
+        .flatten()
+        .map(|c| {
+            let [r, g, b, _] = c.rgba;
+            (
+                c.label_value.to_string(),
+                format!("#{r:02x}{g:02x}{b:02x}").into(),
+            )
+        })
+        .collect();
+    let mut layer = json!({
+        "type": "segmentation",
+        "name": multiscale.name.as_deref().unwrap_or("labels"),
+        "source": source(url, multiscale)?,
+    });
+    if !colors.is_empty() {
+        layer["segmentColors"] = colors.into();
+    }
+    Ok(layer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multiscale(channel: bool) -> MultiscaleImage {
+        let mut axes = vec![
+            json!({"name": "y", "type": "space", "unit": "micrometer"}),
+            json!({"name": "x", "type": "space", "unit": "micrometer"}),
+        ];
+        let mut scale = vec![0.5, 0.25];
+        if channel {
+            axes.insert(0, json!({"name": "c", "type": "channel"}));
+            scale.insert(0, 1.0);
+        }
+        serde_json::from_value(json!({
+            "name": "cells",
+            "axes": axes,
+            "datasets": [{"path": "0", "coordinateTransformations": [
+                {"type": "scale", "scale": scale}
+            ]}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn image_layers_snapshot() {
+        let omero: Omero = serde_json::from_value(json!({"channels": [
+            {"color": "FF0000", "label": "red", "active": false,
+             "window": {"min": 0.0, "max": 255.0, "start": 10.0, "end": 200.0}},
+            {"color": "00FF00",
+             "window": {"min": 0.0, "max": 255.0, "start": 0.0, "end": 255.0}}
+        ]}))
+        .unwrap();
+        let layers = image_layers(
+            "zarr3://s3://bucket/cells.zarr",
+            &multiscale(true),
+            Some(&omero),
+        )
+        .unwrap();
+        let source = json!({
+            "url": "zarr3://s3://bucket/cells.zarr",
+            "transform": {"outputDimensions": {
+                "c'": [1, ""],
+                "y": [5e-7, "m"],
+                "x": [2.5e-7, "m"],
+            }},
+        });
+        assert_eq!(
+            layers[0],
+            json!({
+                "type": "image",
+                "name": "red",
+                "source": source,
+                "visible": false,
+                "blend": "additive",
+                "shader": CHANNEL_SHADER,
+                "shaderControls": {
+                    "contrast": {"range": [10.0, 200.0], "window": [0.0, 255.0]},
+                    "color": "#ff0000",
+                },
+                "localPosition": [0.5],
+            })
+        );
+        assert_eq!(layers[1]["name"], "cells channel 1");
+        assert_eq!(layers[1]["localPosition"], json!([1.5]));
+
+        let layers = image_layers("zarr3://cells.zarr", &multiscale(false), None).unwrap();
+        assert_eq!(layers.len(), 1);
+        assert!(layers[0].get("shader").is_none());
+    }
+
+    #[test]
+    fn segmentation_layer_snapshot() {
+        let label: ImageLabel = serde_json::from_value(json!({"colors": [
+            {"label-value": 1, "rgba": [255, 0, 128, 255]},
+            {"label-value": 7, "rgba": [0, 16, 0, 255]}
+        ]}))
+        .unwrap();
+        let layer = segmentation_layer("zarr3://labels.zarr", &multiscale(false), &label).unwrap();
+        assert_eq!(
+            layer,
+            json!({
+                "type": "segmentation",
+                "name": "cells",
+                "source": {
+                    "url": "zarr3://labels.zarr",
+                    "transform": {"outputDimensions": {
+                        "y": [5e-7, "m"],
+                        "x": [2.5e-7, "m"],
+                    }},
+                },
+                "segmentColors": {"1": "#ff0080", "7": "#001000"},
+            })
+        );
+    }
+}
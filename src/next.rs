@@ -6,7 +6,7 @@ use validatrix::{Accumulator, Validate};
 crate::constrained_version!(ConstrainedVersion, ">=0.6.dev0", "0.6.dev3");
 
 /// OME-Zarr "ome" fields.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct OmeFields {
     /// OME-Zarr version.
     pub version: ConstrainedVersion,
@@ -31,6 +31,9 @@ pub struct OmeFields {
     /// Transitional OMERO metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub omero: Option<Omero>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for OmeFields {
@@ -61,15 +64,23 @@ impl Validate for OmeFields {
 /// OME-Zarr top-level group attributes.
 ///
 /// This can be deserialised from a representation of a group's user attributes.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct OmeZarrGroupAttributes {
+///
+/// Attributes alongside the `ome` key are deserialised into the extension type `E`
+/// and validated with it; by default, they are kept as [RawExtensions].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OmeZarrGroupAttributes<E = RawExtensions> {
     /// OME-Zarr "ome" fields.
     pub ome: OmeFields,
+    /// Attributes other than `ome`.
+    #[serde(flatten)]
+    pub extensions: E,
 }
 
-impl Validate for OmeZarrGroupAttributes {
+impl<E: Validate> Validate for OmeZarrGroupAttributes<E> {
     fn validate_inner(&self, accum: &mut Accumulator) {
         accum.validate_member_at("ome", &self.ome);
+        // fields are flattened into this object
+        self.extensions.validate_inner(accum);
     }
 }
 
@@ -86,16 +97,18 @@ impl TryFrom<prev::OmeFields> for OmeFields {
             plate: value.plate,
             well: value.well,
             omero: value.omero,
+            extra: value.extra,
         })
     }
 }
 
-impl TryFrom<prev::OmeZarrGroupAttributes> for OmeZarrGroupAttributes {
+impl<E> TryFrom<prev::OmeZarrGroupAttributes<E>> for OmeZarrGroupAttributes<E> {
     type Error = crate::Error;
 
-    fn try_from(value: prev::OmeZarrGroupAttributes) -> Result<Self, Self::Error> {
+    fn try_from(value: prev::OmeZarrGroupAttributes<E>) -> Result<Self, Self::Error> {
         Ok(Self {
             ome: OmeFields::try_from(value.ome)?,
+            extensions: value.extensions,
         })
     }
 }
@@ -104,14 +117,51 @@ impl TryFrom<prev::OmeZarrGroupAttributes> for OmeZarrGroupAttributes {
 ///
 /// This can be deserialised from a representation of the whole metadata document
 /// (i.e. the contents of `zarr.json` in zarr v3, which includes user attributes and core metadata).
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct OmeZarrGroupMetadata {
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OmeZarrGroupMetadata<E = RawExtensions> {
+    /// Zarr format version; must be [ZARR_FORMAT].
+    pub zarr_format: u64,
+    /// Zarr node type; must be [NODE_TYPE_GROUP].
+    pub node_type: String,
     /// Zarr attributes with "ome" metadata.
-    pub attributes: OmeZarrGroupAttributes,
+    pub attributes: OmeZarrGroupAttributes<E>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-impl Validate for OmeZarrGroupMetadata {
+impl<E: Validate> Validate for OmeZarrGroupMetadata<E> {
     fn validate_inner(&self, accum: &mut Accumulator) {
+        prev::validate_group_node(accum, self.zarr_format, &self.node_type);
         accum.validate_member_at("attributes", &self.attributes);
     }
 }
+
+impl<E> From<OmeZarrGroupAttributes<E>> for OmeZarrGroupMetadata<E> {
+    fn from(attributes: OmeZarrGroupAttributes<E>) -> Self {
+        Self::new(attributes)
+    }
+}
+
+impl<E> OmeZarrGroupMetadata<E> {
+    /// Group metadata with the given attributes and Zarr v3 group core metadata.
+    pub fn new(attributes: OmeZarrGroupAttributes<E>) -> Self {
+        Self {
+            zarr_format: ZARR_FORMAT,
+            node_type: NODE_TYPE_GROUP.to_string(),
+            attributes,
+            extra: Default::default(),
+        }
+    }
+}
+
+impl<E: Serialize + Validate> OmeZarrGroupMetadata<E> {
+    /// Serialise as the pretty-printed contents of a group's `zarr.json`.
+    ///
+    /// # Errors
+    /// Returns an error if the metadata is invalid, so that only conformant documents are written.
+    pub fn to_zarr_json(&self) -> Result<String, crate::Error> {
+        self.validate()?;
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -0,0 +1,684 @@
+use std::{fmt, str::FromStr};
+
+use roxmltree::Node;
+
+use crate::{Error, Result, v0_5::Bioformats2Raw};
+
+mod convert;
+pub mod units;
+mod write;
+
+/// Path of the OME-XML file relative to the root of a bioformats2raw hierarchy.
+pub const METADATA_PATH: &str = "OME/METADATA.ome.xml";
+
+/// Unit recorded for physical sizes given without a unit, as an OME unit symbol.
+///
+/// Such sizes are treated as uncalibrated, i.e. measured in pixels.
+pub const DEFAULT_LENGTH_UNIT: &str = "pixel";
+
+/// Default unit of time increments, as an OME unit symbol.
+pub const DEFAULT_TIME_UNIT: &str = "s";
+
+/// Default unit of wavelengths, as an OME unit symbol.
+pub const DEFAULT_WAVELENGTH_UNIT: &str = "nm";
+
+/// Root `OME` element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OmeXml {
+    /// Instruments used for acquisition.
+    pub instruments: Vec<Instrument>,
+    /// Images, in document order (which is also series order).
+    pub images: Vec<Image>,
+    /// Plates of a high-content screen.
+    pub plates: Vec<Plate>,
+}
+
+impl OmeXml {
+    /// Parse an OME-XML document.
+    ///
+    /// Elements and attributes which are not represented here are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the document is not well-formed XML,
+    /// if the root element is not `OME`,
+    /// or if a represented element is missing a required attribute or has an invalid value.
+    pub fn parse(text: &str) -> Result<Self> {
+        let doc = roxmltree::Document::parse(text)?;
+        let root = doc.root_element();
+        if root.tag_name().name() != "OME" {
+            return Err(Error::general(format!(
+                "expected root element OME, got {}",
+                root.tag_name().name()
+            )));
+        }
+        Ok(Self {
+            instruments: children(root, "Instrument")
+                .map(Instrument::from_node)
+                .collect::<Result<_>>()?,
+            images: children(root, "Image")
+                .map(Image::from_node)
+                .collect::<Result<_>>()?,
+            plates: children(root, "Plate")
+                .map(Plate::from_node)
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Get the instrument with the given ID.
+    pub fn instrument(&self, id: &str) -> Option<&Instrument> {
+        self.instruments.iter().find(|i| i.id == id)
+    }
+
+    /// Get the image with the given ID.
+    pub fn image(&self, id: &str) -> Option<&Image> {
+        self.images.iter().find(|i| i.id == id)
+    }
+
+    /// Pair each image with the path of its image group in a bioformats2raw hierarchy.
+    ///
+    /// If the `series` field is given, its paths correspond to the images in order;
+    /// otherwise the images are stored in groups named by their index (`"0"`, `"1"`, ...).
+    ///
+    /// # Errors
+    /// Returns an error if the `series` field has a different length to the list of images.
+    pub fn series<'a>(&'a self, layout: &'a Bioformats2Raw) -> Result<Vec<(String, &'a Image)>> {
+        let Some(series) = layout.series.as_ref() else {
+            return Ok(self
+                .images
+                .iter()
+                .enumerate()
+                .map(|(idx, img)| (idx.to_string(), img))
+                .collect());
+        };
+        if series.len() != self.images.len() {
+            return Err(Error::general(format!(
+                "got {} series paths for {} OME-XML images",
+                series.len(),
+                self.images.len()
+            )));
+        }
+        Ok(series.iter().cloned().zip(self.images.iter()).collect())
+    }
+}
+
+impl FromStr for OmeXml {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// A numeric value with an OME unit symbol (e.g. `"µm"`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    /// Numeric value.
+    pub value: f64,
+    /// OME unit symbol.
+    pub unit: String,
+}
+
+/// `Image` element: a single multidimensional image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// Unique ID, e.g. `"Image:0"`.
+    pub id: String,
+    /// Name of the image.
+    pub name: Option<String>,
+    /// Acquisition date, as an ISO 8601 timestamp.
+    pub acquisition_date: Option<String>,
+    /// Free-text description.
+    pub description: Option<String>,
+    /// ID of the instrument used to acquire the image.
+    pub instrument_ref: Option<String>,
+    /// Pixel data description.
+    pub pixels: Pixels,
+}
+
+impl Image {
+    fn from_node(node: Node) -> Result<Self> {
+        let pixels = children(node, "Pixels")
+            .next()
+            .ok_or_else(|| missing(node, "Pixels"))?;
+        Ok(Self {
+            id: required(node, "ID")?.to_string(),
+            name: optional(node, "Name"),
+            acquisition_date: child_text(node, "AcquisitionDate"),
+            description: child_text(node, "Description"),
+            instrument_ref: children(node, "InstrumentRef")
+                .next()
+                .map(|n| required(n, "ID").map(String::from))
+                .transpose()?,
+            pixels: Pixels::from_node(pixels)?,
+        })
+    }
+}
+
+/// Order of dimensions in a [Pixels] buffer, fastest-varying first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DimensionOrder {
+    /// X, Y, Z, C, T.
+    #[default]
+    XYZCT,
+    /// X, Y, Z, T, C.
+    XYZTC,
+    /// X, Y, C, T, Z.
+    XYCTZ,
+    /// X, Y, C, Z, T.
+    XYCZT,
+    /// X, Y, T, C, Z.
+    XYTCZ,
+    /// X, Y, T, Z, C.
+    XYTZC,
+}
+
+impl DimensionOrder {
+    const ALL: [Self; 6] = [
+        Self::XYZCT,
+        Self::XYZTC,
+        Self::XYCTZ,
+        Self::XYCZT,
+        Self::XYTCZ,
+        Self::XYTZC,
+    ];
+
+    /// Dimension order as a string, e.g. `"XYZCT"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::XYZCT => "XYZCT",
+            Self::XYZTC => "XYZTC",
+            Self::XYCTZ => "XYCTZ",
+            Self::XYCZT => "XYCZT",
+            Self::XYTCZ => "XYTCZ",
+            Self::XYTZC => "XYTZC",
+        }
+    }
+
+    /// Dimension names, fastest-varying first.
+    pub fn dimensions(&self) -> [char; 5] {
+        let bytes = self.as_str().as_bytes();
+        std::array::from_fn(|idx| char::from(bytes[idx]))
+    }
+}
+
+impl fmt::Display for DimensionOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DimensionOrder {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|o| o.as_str() == s)
+            .ok_or_else(|| Error::general(format!("invalid DimensionOrder: {s}")))
+    }
+}
+
+/// `Pixels` element: dimensions and physical sizes of an [Image].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pixels {
+    /// Unique ID, e.g. `"Pixels:0"`.
+    pub id: String,
+    /// Order of dimensions in the pixel buffer.
+    pub dimension_order: DimensionOrder,
+    /// Pixel data type, e.g. `"uint16"`.
+    pub pixel_type: String,
+    /// Number of pixels along X.
+    pub size_x: u64,
+    /// Number of pixels along Y.
+    pub size_y: u64,
+    /// Number of Z sections.
+    pub size_z: u64,
+    /// Number of channel samples.
+    pub size_c: u64,
+    /// Number of time points.
+    pub size_t: u64,
+    /// Physical size of a pixel along X.
+    pub physical_size_x: Option<Quantity>,
+    /// Physical size of a pixel along Y.
+    pub physical_size_y: Option<Quantity>,
+    /// Physical distance between Z sections.
+    pub physical_size_z: Option<Quantity>,
+    /// Time between time points.
+    pub time_increment: Option<Quantity>,
+    /// Channels.
+    pub channels: Vec<Channel>,
+}
+
+impl Pixels {
+    fn from_node(node: Node) -> Result<Self> {
+        Ok(Self {
+            id: required(node, "ID")?.to_string(),
+            dimension_order: required(node, "DimensionOrder")?.parse()?,
+            pixel_type: required(node, "Type")?.to_string(),
+            size_x: parse_required(node, "SizeX")?,
+            size_y: parse_required(node, "SizeY")?,
+            size_z: parse_required(node, "SizeZ")?,
+            size_c: parse_required(node, "SizeC")?,
+            size_t: parse_required(node, "SizeT")?,
+            physical_size_x: quantity(node, "PhysicalSizeX", DEFAULT_LENGTH_UNIT)?,
+            physical_size_y: quantity(node, "PhysicalSizeY", DEFAULT_LENGTH_UNIT)?,
+            physical_size_z: quantity(node, "PhysicalSizeZ", DEFAULT_LENGTH_UNIT)?,
+            time_increment: quantity(node, "TimeIncrement", DEFAULT_TIME_UNIT)?,
+            channels: children(node, "Channel")
+                .map(Channel::from_node)
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Number of pixels along the given dimension (`'X'`, `'Y'`, `'Z'`, `'C'`, or `'T'`).
+    pub fn size(&self, dimension: char) -> Option<u64> {
+        match dimension {
+            'X' => Some(self.size_x),
+            'Y' => Some(self.size_y),
+            'Z' => Some(self.size_z),
+            'C' => Some(self.size_c),
+            'T' => Some(self.size_t),
+            _ => None,
+        }
+    }
+}
+
+/// `Channel` element: a channel of a [Pixels] buffer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Channel {
+    /// Unique ID, e.g. `"Channel:0:0"`.
+    pub id: String,
+    /// Name of the channel.
+    pub name: Option<String>,
+    /// Number of samples per pixel (e.g. 3 for RGB).
+    pub samples_per_pixel: Option<u64>,
+    /// Display color, as a signed 32-bit RGBA integer.
+    pub color: Option<i32>,
+    /// Emission wavelength.
+    pub emission_wavelength: Option<Quantity>,
+    /// Excitation wavelength.
+    pub excitation_wavelength: Option<Quantity>,
+    /// Fluorophore.
+    pub fluor: Option<String>,
+}
+
+impl Channel {
+    fn from_node(node: Node) -> Result<Self> {
+        Ok(Self {
+            id: required(node, "ID")?.to_string(),
+            name: optional(node, "Name"),
+            samples_per_pixel: parse_optional(node, "SamplesPerPixel")?,
+            color: parse_optional(node, "Color")?,
+            emission_wavelength: quantity(node, "EmissionWavelength", DEFAULT_WAVELENGTH_UNIT)?,
+            excitation_wavelength: quantity(node, "ExcitationWavelength", DEFAULT_WAVELENGTH_UNIT)?,
+            fluor: optional(node, "Fluor"),
+        })
+    }
+
+    /// Display color as RGBA bytes.
+    pub fn rgba(&self) -> Option<[u8; 4]> {
+        self.color.map(i32::to_be_bytes)
+    }
+}
+
+/// `Instrument` element: the microscope and its components.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Instrument {
+    /// Unique ID, e.g. `"Instrument:0"`.
+    pub id: String,
+    /// The microscope stand.
+    pub microscope: Option<Microscope>,
+    /// Objectives.
+    pub objectives: Vec<Objective>,
+    /// Detectors.
+    pub detectors: Vec<Detector>,
+}
+
+impl Instrument {
+    fn from_node(node: Node) -> Result<Self> {
+        Ok(Self {
+            id: required(node, "ID")?.to_string(),
+            microscope: children(node, "Microscope").next().map(|n| Microscope {
+                manufacturer: optional(n, "Manufacturer"),
+                model: optional(n, "Model"),
+                serial_number: optional(n, "SerialNumber"),
+                r#type: optional(n, "Type"),
+            }),
+            objectives: children(node, "Objective")
+                .map(|n| {
+                    Ok(Objective {
+                        id: required(n, "ID")?.to_string(),
+                        manufacturer: optional(n, "Manufacturer"),
+                        model: optional(n, "Model"),
+                        nominal_magnification: parse_optional(n, "NominalMagnification")?,
+                        lens_na: parse_optional(n, "LensNA")?,
+                        immersion: optional(n, "Immersion"),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            detectors: children(node, "Detector")
+                .map(|n| {
+                    Ok(Detector {
+                        id: required(n, "ID")?.to_string(),
+                        manufacturer: optional(n, "Manufacturer"),
+                        model: optional(n, "Model"),
+                        r#type: optional(n, "Type"),
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// `Microscope` element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Microscope {
+    /// Manufacturer.
+    pub manufacturer: Option<String>,
+    /// Model.
+    pub model: Option<String>,
+    /// Serial number.
+    pub serial_number: Option<String>,
+    /// Type, e.g. `"Inverted"`.
+    pub r#type: Option<String>,
+}
+
+/// `Objective` element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Objective {
+    /// Unique ID, e.g. `"Objective:0:0"`.
+    pub id: String,
+    /// Manufacturer.
+    pub manufacturer: Option<String>,
+    /// Model.
+    pub model: Option<String>,
+    /// Magnification of the lens.
+    pub nominal_magnification: Option<f64>,
+    /// Numerical aperture of the lens.
+    pub lens_na: Option<f64>,
+    /// Immersion medium, e.g. `"Oil"`.
+    pub immersion: Option<String>,
+}
+
+/// `Detector` element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Detector {
+    /// Unique ID, e.g. `"Detector:0:0"`.
+    pub id: String,
+    /// Manufacturer.
+    pub manufacturer: Option<String>,
+    /// Model.
+    pub model: Option<String>,
+    /// Type, e.g. `"CCD"`.
+    pub r#type: Option<String>,
+}
+
+/// `Plate` element: a plate of a high-content screen.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plate {
+    /// Unique ID, e.g. `"Plate:0"`.
+    pub id: String,
+    /// Name of the plate.
+    pub name: Option<String>,
+    /// Number of rows.
+    pub rows: Option<u64>,
+    /// Number of columns.
+    pub columns: Option<u64>,
+    /// Naming convention of rows: `"letter"` or `"number"`.
+    pub row_naming_convention: Option<String>,
+    /// Naming convention of columns: `"letter"` or `"number"`.
+    pub column_naming_convention: Option<String>,
+    /// Wells.
+    pub wells: Vec<Well>,
+    /// Acquisition runs.
+    pub plate_acquisitions: Vec<PlateAcquisition>,
+}
+
+impl Plate {
+    fn from_node(node: Node) -> Result<Self> {
+        Ok(Self {
+            id: required(node, "ID")?.to_string(),
+            name: optional(node, "Name"),
+            rows: parse_optional(node, "Rows")?,
+            columns: parse_optional(node, "Columns")?,
+            row_naming_convention: optional(node, "RowNamingConvention"),
+            column_naming_convention: optional(node, "ColumnNamingConvention"),
+            wells: children(node, "Well")
+                .map(Well::from_node)
+                .collect::<Result<_>>()?,
+            plate_acquisitions: children(node, "PlateAcquisition")
+                .map(PlateAcquisition::from_node)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// `Well` element: a well of a [Plate].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Well {
+    /// Unique ID, e.g. `"Well:0"`.
+    pub id: String,
+    /// Zero-based row index.
+    pub row: u64,
+    /// Zero-based column index.
+    pub column: u64,
+    /// Fields of view.
+    pub samples: Vec<WellSample>,
+}
+
+impl Well {
+    fn from_node(node: Node) -> Result<Self> {
+        Ok(Self {
+            id: required(node, "ID")?.to_string(),
+            row: parse_required(node, "Row")?,
+            column: parse_required(node, "Column")?,
+            samples: children(node, "WellSample")
+                .map(|n| {
+                    Ok(WellSample {
+                        id: required(n, "ID")?.to_string(),
+                        index: parse_required(n, "Index")?,
+                        image_ref: children(n, "ImageRef")
+                            .next()
+                            .map(|r| required(r, "ID").map(String::from))
+                            .transpose()?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// `WellSample` element: a field of view of a [Well].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WellSample {
+    /// Unique ID, e.g. `"WellSample:0:0"`.
+    pub id: String,
+    /// Index of the field of view within the plate.
+    pub index: u64,
+    /// ID of the image of this field of view.
+    pub image_ref: Option<String>,
+}
+
+/// `PlateAcquisition` element: an acquisition run of a [Plate].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlateAcquisition {
+    /// Unique ID, e.g. `"PlateAcquisition:0"`.
+    pub id: String,
+    /// Name of the acquisition.
+    pub name: Option<String>,
+    /// Maximum number of fields of view per well.
+    pub maximum_field_count: Option<u64>,
+    /// Start time, as an ISO 8601 timestamp.
+    pub start_time: Option<String>,
+    /// End time, as an ISO 8601 timestamp.
+    pub end_time: Option<String>,
+    /// IDs of the well samples acquired in this run.
+    pub well_sample_refs: Vec<String>,
+}
+
+impl PlateAcquisition {
+    fn from_node(node: Node) -> Result<Self> {
+        Ok(Self {
+            id: required(node, "ID")?.to_string(),
+            name: optional(node, "Name"),
+            maximum_field_count: parse_optional(node, "MaximumFieldCount")?,
+            start_time: optional(node, "StartTime"),
+            end_time: optional(node, "EndTime"),
+            well_sample_refs: children(node, "WellSampleRef")
+                .map(|n| required(n, "ID").map(String::from))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// Child elements with the given local name, ignoring namespaces.
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn child_text(node: Node, name: &'static str) -> Option<String> {
+    children(node, name)
+        .next()
+        .and_then(|n| n.text())
+        .map(String::from)
+}
+
+fn missing(node: Node, name: &str) -> Error {
+    Error::general(format!(
+        "{} element is missing {name}",
+        node.tag_name().name()
+    ))
+}
+
+fn required<'a>(node: Node<'a, '_>, name: &str) -> Result<&'a str> {
+    node.attribute(name).ok_or_else(|| missing(node, name))
+}
+
+fn optional(node: Node, name: &str) -> Option<String> {
+    node.attribute(name).map(String::from)
+}
+
+fn parse_value<T: FromStr>(node: Node, name: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| {
+        Error::general(format!(
+            "invalid {} {name}: {value}",
+            node.tag_name().name()
+        ))
+    })
+}
+
+fn parse_required<T: FromStr>(node: Node, name: &str) -> Result<T> {
+    parse_value(node, name, required(node, name)?)
+}
+
+fn parse_optional<T: FromStr>(node: Node, name: &str) -> Result<Option<T>> {
+    node.attribute(name)
+        .map(|v| parse_value(node, name, v))
+        .transpose()
+}
+
+/// Parse a value attribute along with its `{name}Unit` attribute.
+fn quantity(node: Node, name: &str, default_unit: &str) -> Result<Option<Quantity>> {
+    let Some(value) = parse_optional(node, name)? else {
+        return Ok(None);
+    };
+    let unit = node
+        .attribute(format!("{name}Unit").as_str())
+        .unwrap_or(default_unit)
+        .to_string();
+    Ok(Some(Quantity { value, unit }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OME xmlns="http://www.openmicroscopy.org/Schemas/OME/2016-06">
+  <Instrument ID="Instrument:0">
+    <Microscope Manufacturer="Zeiss" Type="Inverted"/>
+    <Objective ID="Objective:0:0" NominalMagnification="40.0" LensNA="1.3" Immersion="Oil"/>
+    <Detector ID="Detector:0:0" Type="CMOS"/>
+  </Instrument>
+  <Image ID="Image:0" Name="A1 field 0">
+    <AcquisitionDate>2024-05-01T10:00:00</AcquisitionDate>
+    <InstrumentRef ID="Instrument:0"/>
+    <Pixels ID="Pixels:0" DimensionOrder="XYCZT" Type="uint16" SizeX="512" SizeY="256" SizeZ="10" SizeC="2" SizeT="1"
+        PhysicalSizeX="0.325" PhysicalSizeY="0.325" PhysicalSizeZ="1.5" PhysicalSizeZUnit="nm">
+      <Channel ID="Channel:0:0" Name="DAPI" Color="65535" SamplesPerPixel="1" EmissionWavelength="461"/>
+      <Channel ID="Channel:0:1" Name="GFP" Color="16711935"/>
+    </Pixels>
+  </Image>
+  <Image ID="Image:1">
+    <Pixels ID="Pixels:1" DimensionOrder="XYZCT" Type="uint8" SizeX="64" SizeY="64" SizeZ="1" SizeC="1" SizeT="5" TimeIncrement="250" TimeIncrementUnit="ms"/>
+  </Image>
+  <Plate ID="Plate:0" Name="screen" Rows="8" Columns="12" RowNamingConvention="letter" ColumnNamingConvention="number">
+    <Well ID="Well:0" Row="0" Column="0">
+      <WellSample ID="WellSample:0:0" Index="0"><ImageRef ID="Image:0"/></WellSample>
+      <WellSample ID="WellSample:0:1" Index="1"><ImageRef ID="Image:1"/></WellSample>
+    </Well>
+    <PlateAcquisition ID="PlateAcquisition:0" MaximumFieldCount="2">
+      <WellSampleRef ID="WellSample:0:0"/>
+      <WellSampleRef ID="WellSample:0:1"/>
+    </PlateAcquisition>
+  </Plate>
+</OME>"#;
+
+    #[test]
+    fn parse_example() {
+        let ome: OmeXml = EXAMPLE.parse().unwrap();
+        assert_eq!(ome.images.len(), 2);
+        let img = &ome.images[0];
+        assert_eq!(img.acquisition_date.as_deref(), Some("2024-05-01T10:00:00"));
+        assert_eq!(
+            ome.instrument(img.instrument_ref.as_deref().unwrap())
+                .unwrap()
+                .objectives[0]
+                .lens_na,
+            Some(1.3)
+        );
+
+        let px = &img.pixels;
+        assert_eq!(px.dimension_order, DimensionOrder::XYCZT);
+        assert_eq!(px.dimension_order.dimensions(), ['X', 'Y', 'C', 'Z', 'T']);
+        assert_eq!(px.physical_size_x.as_ref().unwrap().unit, "pixel");
+        assert_eq!(px.physical_size_z.as_ref().unwrap().unit, "nm");
+        assert_eq!(px.channels[0].rgba(), Some([0, 0, 255, 255]));
+        let dt = ome.images[1].pixels.time_increment.as_ref().unwrap();
+        assert_eq!((dt.value, dt.unit.as_str()), (250.0, "ms"));
+
+        let plate = &ome.plates[0];
+        assert_eq!(
+            plate.wells[0].samples[1].image_ref.as_deref(),
+            Some("Image:1")
+        );
+        assert_eq!(plate.plate_acquisitions[0].well_sample_refs.len(), 2);
+
+        assert_eq!(OmeXml::parse(&ome.to_xml()).unwrap(), ome);
+    }
+
+    #[test]
+    fn series_paths() {
+        let ome = OmeXml::parse(EXAMPLE).unwrap();
+        let layout: Bioformats2Raw =
+            serde_json::from_str(r#"{"bioformats2raw.layout": 3}"#).unwrap();
+        let series = ome.series(&layout).unwrap();
+        assert_eq!(series[1].0, "1");
+        assert_eq!(series[1].1.id, "Image:1");
+
+        let layout: Bioformats2Raw =
+            serde_json::from_str(r#"{"bioformats2raw.layout": 3, "series": ["A/1/0", "A/1/1"]}"#)
+                .unwrap();
+        let series = ome.series(&layout).unwrap();
+        assert_eq!(series[0].0, "A/1/0");
+
+        let layout: Bioformats2Raw =
+            serde_json::from_str(r#"{"bioformats2raw.layout": 3, "series": ["0"]}"#).unwrap();
+        assert!(ome.series(&layout).is_err());
+    }
+}
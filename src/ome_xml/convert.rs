@@ -0,0 +1,301 @@
+//! Deriving OME-Zarr metadata from OME-XML.
+
+use super::{Image, Pixels, Quantity, units};
+use crate::{
+    Error, Result,
+    v0_5::{
+        Axis, AxisType, AxisUnit, Channel as OmeroChannel, Color, CoordinateTransform,
+        MultiscaleImage, MultiscaleImageDataset, OmeFields, Omero, Window,
+    },
+};
+
+/// OME length units which have no physical equivalent, so are dropped rather than rejected.
+const UNCALIBRATED_UNITS: [&str; 2] = ["pixel", "reference frame"];
+
+/// Display color of channels with no `Color`, as in the OME schema.
+const DEFAULT_COLOR: Color = Color {
+    r: u8::MAX,
+    g: u8::MAX,
+    b: u8::MAX,
+};
+
+/// A physical size, unless it is missing or uncalibrated.
+fn calibrated(quantity: Option<&Quantity>) -> Option<&Quantity> {
+    quantity.filter(|q| !UNCALIBRATED_UNITS.contains(&q.unit.as_str()))
+}
+
+fn space_unit(quantity: Option<&Quantity>) -> Result<Option<AxisUnit>> {
+    let Some(q) = calibrated(quantity) else {
+        return Ok(None);
+    };
+    units::space_from_ome(&q.unit)
+        .map(|u| Some(u.into()))
+        .ok_or_else(|| Error::general(format!("unsupported OME length unit: {}", q.unit)))
+}
+
+fn time_unit(quantity: Option<&Quantity>) -> Result<Option<AxisUnit>> {
+    let Some(q) = quantity else {
+        return Ok(None);
+    };
+    units::time_from_ome(&q.unit)
+        .map(|u| Some(u.into()))
+        .ok_or_else(|| Error::general(format!("unsupported OME time unit: {}", q.unit)))
+}
+
+/// Display range of a pixel type, for OMERO channel windows.
+fn pixel_range(pixel_type: &str) -> (f64, f64) {
+    match pixel_type {
+        "int8" => (i8::MIN.into(), i8::MAX.into()),
+        "int16" => (i16::MIN.into(), i16::MAX.into()),
+        "int32" => (i32::MIN.into(), i32::MAX.into()),
+        "uint8" => (0.0, u8::MAX.into()),
+        "uint16" => (0.0, u16::MAX.into()),
+        "uint32" => (0.0, u32::MAX.into()),
+        "bit" => (0.0, 1.0),
+        // floating point and complex data have no natural range
+        _ => (0.0, 1.0),
+    }
+}
+
+impl Pixels {
+    /// OME-Zarr axes `t`, `c`, `z`, `y`, `x`, in that order, with units from the OME-XML.
+    ///
+    /// The order is fixed, as in arrays written by bioformats2raw,
+    /// and does not follow [Pixels::dimension_order];
+    /// callers must check that it matches the array being described.
+    ///
+    /// # Errors
+    /// Returns an error if a physical size or time increment has a unit which cannot be
+    /// represented in OME-Zarr (see [units]).
+    /// `"pixel"` and `"reference frame"` lengths are treated as having no unit.
+    pub fn axes(&self) -> Result<Vec<Axis>> {
+        let axis = |name: &str, r#type, unit| Axis {
+            name: name.to_string(),
+            r#type: Some(r#type),
+            unit,
+            extra: Default::default(),
+        };
+        Ok(vec![
+            axis(
+                "t",
+                AxisType::Time,
+                time_unit(self.time_increment.as_ref())?,
+            ),
+            axis("c", AxisType::Channel, None),
+            axis(
+                "z",
+                AxisType::Space,
+                space_unit(self.physical_size_z.as_ref())?,
+            ),
+            axis(
+                "y",
+                AxisType::Space,
+                space_unit(self.physical_size_y.as_ref())?,
+            ),
+            axis(
+                "x",
+                AxisType::Space,
+                space_unit(self.physical_size_x.as_ref())?,
+            ),
+        ])
+    }
+
+    /// Full-resolution scale of the axes given by [Pixels::axes].
+    ///
+    /// Dimensions without a physical size, or whose size is in pixels
+    /// (including sizes given without a unit), have a scale of 1.
+    pub fn scale(&self) -> Vec<f64> {
+        let value = |q: Option<&Quantity>| q.map_or(1.0, |q| q.value);
+        vec![
+            value(self.time_increment.as_ref()),
+            1.0,
+            value(calibrated(self.physical_size_z.as_ref())),
+            value(calibrated(self.physical_size_y.as_ref())),
+            value(calibrated(self.physical_size_x.as_ref())),
+        ]
+    }
+
+    /// Describe this image as an OME-Zarr multiscale image with the given number of levels.
+    ///
+    /// As written by bioformats2raw, levels are stored at paths `"0"`, `"1"`, etc.
+    /// and each level is downsampled by `xy_factor` in `x` and `y` relative to the previous.
+    ///
+    /// # Errors
+    /// Returns an error if there are no levels, or see [Pixels::axes].
+    pub fn to_multiscale_image(&self, levels: usize, xy_factor: f64) -> Result<MultiscaleImage> {
+        if levels == 0 {
+            return Err(Error::general(
+                "multiscale image must have at least 1 level",
+            ));
+        }
+        let base = self.scale();
+        let datasets = (0..levels)
+            .map(|level| {
+                let factor = xy_factor.powi(level as i32);
+                let scale: Vec<f64> = base
+                    .iter()
+                    .enumerate()
+                    // only y and x are downsampled
+                    .map(|(idx, s)| if idx >= 3 { s * factor } else { *s })
+                    .collect();
+                MultiscaleImageDataset {
+                    path: level.to_string(),
+                    coordinate_transformations: vec![CoordinateTransform::Scale(scale.into())],
+                    extra: Default::default(),
+                }
+            })
+            .collect();
+        Ok(MultiscaleImage {
+            name: None,
+            axes: self.axes()?,
+            datasets,
+            coordinate_transformations: None,
+            r#type: None,
+            metadata: None,
+            extra: Default::default(),
+        })
+    }
+
+    /// Describe the channels as OMERO rendering metadata.
+    ///
+    /// Channel names become labels, and colors are converted from RGBA (ignoring alpha),
+    /// defaulting to white.
+    /// Windows span the full range of the pixel type, or `[0, 1]` for floating point data.
+    pub fn to_omero(&self) -> Omero {
+        let (min, max) = pixel_range(&self.pixel_type);
+        let channels = self
+            .channels
+            .iter()
+            .map(|ch| {
+                let color = ch
+                    .rgba()
+                    .map_or(DEFAULT_COLOR, |[r, g, b, _]| Color { r, g, b });
+                let mut other = serde_json::Map::default();
+                if let Some(name) = ch.name.as_ref() {
+                    other.insert("label".into(), name.clone().into());
+                }
+                other.insert("active".into(), true.into());
+                OmeroChannel {
+                    color,
+                    window: Window {
+                        min,
+                        max,
+                        start: min,
+                        end: max,
+                        extra: Default::default(),
+                    },
+                    other,
+                }
+            })
+            .collect();
+        Omero {
+            channels,
+            other: Default::default(),
+        }
+    }
+}
+
+impl Image {
+    /// Describe this image as OME-Zarr metadata, with `multiscales` and `omero` fields.
+    ///
+    /// See [Pixels::to_multiscale_image] and [Pixels::to_omero];
+    /// the multiscale image is named after this image.
+    /// OMERO metadata is only included if the OME-XML lists channels.
+    ///
+    /// # Errors
+    /// See [Pixels::to_multiscale_image].
+    pub fn to_ome_fields(&self, levels: usize, xy_factor: f64) -> Result<OmeFields> {
+        let mut multiscale = self.pixels.to_multiscale_image(levels, xy_factor)?;
+        multiscale.name = self.name.clone();
+        Ok(OmeFields {
+            multiscales: Some(vec![multiscale]),
+            omero: (!self.pixels.channels.is_empty()).then(|| self.pixels.to_omero()),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use validatrix::Validate;
+
+    use super::*;
+    use crate::{ome_xml::OmeXml, v0_5::AxisUnitSpace};
+
+    #[test]
+    fn pixels_to_ome_fields() {
+        let ome = OmeXml::parse(
+            r#"<OME xmlns="http://www.openmicroscopy.org/Schemas/OME/2016-06">
+              <Image ID="Image:0" Name="cells">
+                <Pixels ID="Pixels:0" DimensionOrder="XYCZT" Type="uint16" SizeX="512" SizeY="512" SizeZ="5" SizeC="2" SizeT="3"
+                    PhysicalSizeX="0.5" PhysicalSizeXUnit="um" PhysicalSizeY="0.5" PhysicalSizeZ="2" PhysicalSizeZUnit="pixel"
+                    TimeIncrement="30" TimeIncrementUnit="min">
+                  <Channel ID="Channel:0:0" Name="DAPI" Color="65535"/>
+                  <Channel ID="Channel:0:1"/>
+                </Pixels>
+              </Image>
+            </OME>"#,
+        )
+        .unwrap();
+        let fields = ome.images[0].to_ome_fields(3, 2.0).unwrap();
+        fields.validate().unwrap();
+
+        let ms = &fields.multiscales.as_ref().unwrap()[0];
+        assert_eq!(ms.name.as_deref(), Some("cells"));
+        assert_eq!(
+            ms.axes[4].unit,
+            Some(AxisUnit::Space(AxisUnitSpace::Micrometer))
+        );
+        // no unit for y, and z is in pixels
+        assert_eq!(ms.axes[3].unit, None);
+        assert_eq!(ms.axes[2].unit, None);
+        let tfs = ms.level_transforms().unwrap();
+        assert_eq!(tfs[2].scale, vec![30.0, 1.0, 1.0, 4.0, 2.0]);
+
+        let omero = fields.omero.as_ref().unwrap();
+        assert_eq!(omero.channels[0].color, Color { r: 0, g: 0, b: 255 });
+        assert_eq!(omero.channels[1].color, DEFAULT_COLOR);
+        assert_eq!(omero.channels[0].window.max, 65535.0);
+        assert_eq!(omero.channels[0].other["label"], "DAPI");
+    }
+
+    #[test]
+    fn unsupported_unit() {
+        let mut pixels = OmeXml::parse(
+            r#"<OME><Image ID="Image:0"><Pixels ID="Pixels:0" DimensionOrder="XYZCT" Type="uint8"
+                SizeX="1" SizeY="1" SizeZ="1" SizeC="1" SizeT="1" PhysicalSizeX="1" PhysicalSizeXUnit="ly"/></Image></OME>"#,
+        )
+        .unwrap()
+        .images
+        .remove(0)
+        .pixels;
+        assert!(pixels.axes().is_err());
+        pixels.physical_size_x = None;
+        assert!(pixels.axes().is_ok());
+    }
+
+    #[test]
+    fn uncalibrated_sizes() {
+        let pixels = OmeXml::parse(
+            r#"<OME><Image ID="Image:0"><Pixels ID="Pixels:0" DimensionOrder="XYZCT" Type="uint8"
+                SizeX="1" SizeY="1" SizeZ="1" SizeC="1" SizeT="1"
+                PhysicalSizeX="0.2" PhysicalSizeY="0.3" PhysicalSizeYUnit="pixel" PhysicalSizeZ="4" PhysicalSizeZUnit="nm"/></Image></OME>"#,
+        )
+        .unwrap()
+        .images
+        .remove(0)
+        .pixels;
+        assert_eq!(pixels.scale(), vec![1.0, 1.0, 4.0, 1.0, 1.0]);
+        let units: Vec<_> = pixels.axes().unwrap().into_iter().map(|a| a.unit).collect();
+        assert_eq!(
+            units,
+            vec![
+                None,
+                None,
+                Some(AxisUnit::Space(AxisUnitSpace::Nanometer)),
+                None,
+                None
+            ]
+        );
+    }
+}
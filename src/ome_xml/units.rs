@@ -0,0 +1,134 @@
+//! Mapping between OME unit symbols and the UDUNITS-2 names used by OME-Zarr axes.
+
+use crate::v0_5::{AxisUnitSpace, AxisUnitTime};
+
+const SPACE_UNITS: &[(AxisUnitSpace, &str)] = &[
+    (AxisUnitSpace::Angstrom, "Å"),
+    (AxisUnitSpace::Attometer, "am"),
+    (AxisUnitSpace::Centimeter, "cm"),
+    (AxisUnitSpace::Decimeter, "dm"),
+    (AxisUnitSpace::Exameter, "Em"),
+    (AxisUnitSpace::Femtometer, "fm"),
+    (AxisUnitSpace::Foot, "ft"),
+    (AxisUnitSpace::Gigameter, "Gm"),
+    (AxisUnitSpace::Hectometer, "hm"),
+    (AxisUnitSpace::Inch, "in"),
+    (AxisUnitSpace::Kilometer, "km"),
+    (AxisUnitSpace::Megameter, "Mm"),
+    (AxisUnitSpace::Meter, "m"),
+    (AxisUnitSpace::Micrometer, "µm"),
+    (AxisUnitSpace::Mile, "mi"),
+    (AxisUnitSpace::Millimeter, "mm"),
+    (AxisUnitSpace::Nanometer, "nm"),
+    (AxisUnitSpace::Parsec, "pc"),
+    (AxisUnitSpace::Petameter, "Pm"),
+    (AxisUnitSpace::Picometer, "pm"),
+    (AxisUnitSpace::Terameter, "Tm"),
+    (AxisUnitSpace::Yard, "yd"),
+    (AxisUnitSpace::Yoctometer, "ym"),
+    (AxisUnitSpace::Yottameter, "Ym"),
+    (AxisUnitSpace::Zeptometer, "zm"),
+    (AxisUnitSpace::Zettameter, "Zm"),
+];
+
+const TIME_UNITS: &[(AxisUnitTime, &str)] = &[
+    (AxisUnitTime::Attosecond, "as"),
+    (AxisUnitTime::Centisecond, "cs"),
+    (AxisUnitTime::Day, "d"),
+    (AxisUnitTime::Decisecond, "ds"),
+    (AxisUnitTime::Exasecond, "Es"),
+    (AxisUnitTime::Femtosecond, "fs"),
+    (AxisUnitTime::Gigasecond, "Gs"),
+    (AxisUnitTime::Hectosecond, "hs"),
+    (AxisUnitTime::Hour, "h"),
+    (AxisUnitTime::Kilosecond, "ks"),
+    (AxisUnitTime::Megasecond, "Ms"),
+    (AxisUnitTime::Microsecond, "µs"),
+    (AxisUnitTime::Millisecond, "ms"),
+    (AxisUnitTime::Minute, "min"),
+    (AxisUnitTime::Nanosecond, "ns"),
+    (AxisUnitTime::Petasecond, "Ps"),
+    (AxisUnitTime::Picosecond, "ps"),
+    (AxisUnitTime::Second, "s"),
+    (AxisUnitTime::Terasecond, "Ts"),
+    (AxisUnitTime::Yoctosecond, "ys"),
+    (AxisUnitTime::Yottasecond, "Ys"),
+    (AxisUnitTime::Zeptosecond, "zs"),
+    (AxisUnitTime::Zettasecond, "Zs"),
+];
+
+/// OME symbol of a space unit, e.g. `"µm"` for [AxisUnitSpace::Micrometer].
+pub fn space_to_ome(unit: AxisUnitSpace) -> Option<&'static str> {
+    SPACE_UNITS
+        .iter()
+        .find_map(|(u, symbol)| (*u == unit).then_some(*symbol))
+}
+
+/// OME symbol of a time unit, e.g. `"ms"` for [AxisUnitTime::Millisecond].
+pub fn time_to_ome(unit: AxisUnitTime) -> Option<&'static str> {
+    TIME_UNITS
+        .iter()
+        .find_map(|(u, symbol)| (*u == unit).then_some(*symbol))
+}
+
+/// Alternative spellings of OME unit symbols, normalised before lookup.
+const ALIASES: &[(&str, &str)] = &[
+    // GREEK SMALL LETTER MU rather than MICRO SIGN
+    ("\u{3bc}m", "µm"),
+    ("\u{3bc}s", "µs"),
+    ("um", "µm"),
+    ("micron", "µm"),
+    ("us", "µs"),
+    // ANGSTROM SIGN rather than LATIN CAPITAL LETTER A WITH RING ABOVE
+    ("\u{212b}", "Å"),
+    ("sec", "s"),
+];
+
+fn normalise(symbol: &str) -> &str {
+    ALIASES
+        .iter()
+        .find_map(|(alias, canonical)| (*alias == symbol).then_some(*canonical))
+        .unwrap_or(symbol)
+}
+
+/// Space unit of an OME symbol, e.g. [AxisUnitSpace::Micrometer] for `"µm"`.
+///
+/// Common alternative spellings such as `"um"` are accepted.
+/// Returns `None` for OME length units without a UDUNITS-2 equivalent in OME-Zarr,
+/// such as `"pixel"` and `"reference frame"`.
+pub fn space_from_ome(symbol: &str) -> Option<AxisUnitSpace> {
+    let symbol = normalise(symbol);
+    SPACE_UNITS
+        .iter()
+        .find_map(|(u, s)| (*s == symbol).then_some(*u))
+}
+
+/// Time unit of an OME symbol, e.g. [AxisUnitTime::Millisecond] for `"ms"`.
+///
+/// Common alternative spellings such as `"us"` are accepted.
+pub fn time_from_ome(symbol: &str) -> Option<AxisUnitTime> {
+    let symbol = normalise(symbol);
+    TIME_UNITS
+        .iter()
+        .find_map(|(u, s)| (*s == symbol).then_some(*u))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for (unit, symbol) in SPACE_UNITS {
+            assert_eq!(space_to_ome(*unit), Some(*symbol));
+            assert_eq!(space_from_ome(symbol), Some(*unit));
+        }
+        for (unit, symbol) in TIME_UNITS {
+            assert_eq!(time_to_ome(*unit), Some(*symbol));
+            assert_eq!(time_from_ome(symbol), Some(*unit));
+        }
+        assert_eq!(space_from_ome("um"), Some(AxisUnitSpace::Micrometer));
+        assert_eq!(space_from_ome("\u{3bc}m"), Some(AxisUnitSpace::Micrometer));
+        assert_eq!(space_from_ome("pixel"), None);
+    }
+}
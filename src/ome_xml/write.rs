@@ -0,0 +1,663 @@
+//! Generating OME-XML from OME-Zarr metadata.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+    Channel, DimensionOrder, Image, Instrument, OmeXml, Pixels, Plate, PlateAcquisition, Quantity,
+    Well, WellSample, units,
+};
+use crate::{
+    Error, Result,
+    v0_5::{self, Axis, AxisType, AxisUnit, MultiscaleImage, Omero},
+    xml::escape_xml,
+};
+
+const NAMESPACE: &str = "http://www.openmicroscopy.org/Schemas/OME/2016-06";
+const SCHEMA_LOCATION: &str = "http://www.openmicroscopy.org/Schemas/OME/2016-06 http://www.openmicroscopy.org/Schemas/OME/2016-06/ome.xsd";
+
+/// OME-XML dimension (`'X'`, `'Y'`, `'Z'`, `'C'`, or `'T'`) of an OME-Zarr axis,
+/// by its type, falling back to its name.
+pub(crate) fn axis_dimension(axis: &Axis) -> Option<char> {
+    let name = axis.name.to_ascii_uppercase();
+    match (&axis.r#type, name.as_str()) {
+        (Some(AxisType::Channel), _) => Some('C'),
+        (Some(AxisType::Time), _) => Some('T'),
+        (Some(AxisType::Space) | None, "X" | "Y" | "Z") | (None, "C" | "T") => name.chars().next(),
+        _ => None,
+    }
+}
+
+impl OmeXml {
+    /// Build a minimal OME-XML document describing the images or plate in the given metadata.
+    ///
+    /// Each multiscale image is described with [Image::from_multiscale],
+    /// given the shape and pixel type of its full-resolution array from `arrays`, in the same order.
+    ///
+    /// A plate is described with [Plate::from_plate], given the metadata of its wells by path.
+    /// Its fields of view only refer to images described in the same document,
+    /// so those of a plate group, which has no multiscale images of its own, have no `ImageRef`.
+    /// A well group on its own is not described, as OME-XML has no wells outside plates.
+    ///
+    /// # Errors
+    /// Returns an error if the number of `arrays` does not match the number of multiscale images,
+    /// or see [Image::from_multiscale].
+    pub fn from_ome_fields(
+        fields: &v0_5::OmeFields,
+        arrays: &[(&[u64], &str)],
+        wells: &BTreeMap<String, v0_5::Well>,
+    ) -> Result<Self> {
+        let multiscales = fields.multiscales.as_deref().unwrap_or_default();
+        if multiscales.len() != arrays.len() {
+            return Err(Error::general(format!(
+                "got {} arrays for {} multiscale images",
+                arrays.len(),
+                multiscales.len()
+            )));
+        }
+        let images: Vec<Image> = multiscales
+            .iter()
+            .zip(arrays)
+            .enumerate()
+            .map(|(index, (multiscale, (shape, pixel_type)))| {
+                Image::from_multiscale(index, multiscale, fields.omero.as_ref(), shape, pixel_type)
+            })
+            .collect::<Result<_>>()?;
+
+        let mut plates: Vec<Plate> = fields
+            .plate
+            .iter()
+            .map(|plate| Plate::from_plate(0, plate, wells).0)
+            .collect();
+        let image_ids: BTreeSet<&str> = images.iter().map(|i| i.id.as_str()).collect();
+        for sample in plates
+            .iter_mut()
+            .flat_map(|p| p.wells.iter_mut())
+            .flat_map(|w| w.samples.iter_mut())
+        {
+            if sample
+                .image_ref
+                .as_deref()
+                .is_some_and(|id| !image_ids.contains(id))
+            {
+                sample.image_ref = None;
+            }
+        }
+
+        Ok(Self {
+            images,
+            plates,
+            ..Default::default()
+        })
+    }
+
+    /// Serialise as an OME-XML document.
+    pub fn to_xml(&self) -> String {
+        let root = Element::new("OME")
+            .attr("xmlns", NAMESPACE)
+            .attr("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance")
+            .attr("xsi:schemaLocation", SCHEMA_LOCATION)
+            .children(self.plates.iter().map(Plate::to_element))
+            .children(self.instruments.iter().map(Instrument::to_element))
+            .children(self.images.iter().map(Image::to_element));
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        root.write(&mut out, 0);
+        out
+    }
+}
+
+impl Image {
+    /// Describe the full-resolution level of a multiscale image, with the given ID index
+    /// (e.g. `0` for `"Image:0"`).
+    ///
+    /// `shape` and `pixel_type` (e.g. `"uint16"`) describe the full-resolution array,
+    /// which are not part of the OME-Zarr metadata.
+    /// Physical sizes and the time increment are taken from the full-resolution scale
+    /// of axes with a recognised unit.
+    /// Channel names and colors are taken from `omero`, if given.
+    ///
+    /// # Errors
+    /// Returns an error if the shape does not match the axes,
+    /// if an axis does not correspond to an OME-XML dimension (or several do),
+    /// if the `x` and `y` axes are not the last two axes,
+    /// if the number of `omero` channels does not match the channel axis,
+    /// or if the transformations cannot be resolved (see [MultiscaleImage::level_transforms]).
+    pub fn from_multiscale(
+        index: usize,
+        multiscale: &MultiscaleImage,
+        omero: Option<&Omero>,
+        shape: &[u64],
+        pixel_type: &str,
+    ) -> Result<Self> {
+        if shape.len() != multiscale.axes.len() {
+            return Err(Error::NDimMismatch {
+                expected: multiscale.axes.len(),
+                actual: shape.len(),
+            });
+        }
+        let scale = multiscale
+            .level_transforms()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::general("multiscale image has no datasets"))?
+            .scale;
+
+        let mut sizes = BTreeMap::default();
+        let mut physical = BTreeMap::default();
+        let mut order = String::default();
+        for ((axis, len), scale) in multiscale.axes.iter().zip(shape).zip(scale).rev() {
+            let dim = axis_dimension(axis).ok_or_else(|| {
+                Error::general(format!("axis {:?} has no OME-XML dimension", axis.name))
+            })?;
+            if sizes.insert(dim, *len).is_some() {
+                return Err(Error::general(format!(
+                    "several axes correspond to OME-XML dimension {dim}"
+                )));
+            }
+            order.push(dim);
+            let unit = match &axis.unit {
+                Some(AxisUnit::Space(u)) => units::space_to_ome(*u),
+                Some(AxisUnit::Time(u)) => units::time_to_ome(*u),
+                _ => None,
+            };
+            if let Some(unit) = unit {
+                physical.insert(
+                    dim,
+                    Quantity {
+                        value: scale,
+                        unit: unit.to_string(),
+                    },
+                );
+            }
+        }
+        for dim in ['Z', 'C', 'T'] {
+            if !order.contains(dim) {
+                order.push(dim);
+            }
+        }
+        let dimension_order: DimensionOrder = order.parse().map_err(|_| {
+            Error::general(format!(
+                "axes give dimension order {order}, but OME-XML requires x and y to be the last axes"
+            ))
+        })?;
+        let size = |dim| sizes.get(&dim).copied().unwrap_or(1);
+
+        let size_c = size('C');
+        let channels = match omero {
+            Some(omero) => {
+                if omero.channels.len() as u64 != size_c {
+                    return Err(Error::general(format!(
+                        "got {} omero channels for {size_c} channels",
+                        omero.channels.len()
+                    )));
+                }
+                omero
+                    .channels
+                    .iter()
+                    .enumerate()
+                    .map(|(c, ch)| Channel {
+                        id: format!("Channel:{index}:{c}"),
+                        name: ch
+                            .other
+                            .get("label")
+                            .and_then(|l| l.as_str())
+                            .map(String::from),
+                        samples_per_pixel: Some(1),
+                        color: Some(i32::from_be_bytes([
+                            ch.color.r,
+                            ch.color.g,
+                            ch.color.b,
+                            u8::MAX,
+                        ])),
+                        ..Default::default()
+                    })
+                    .collect()
+            }
+            None => (0..size_c)
+                .map(|c| Channel {
+                    id: format!("Channel:{index}:{c}"),
+                    samples_per_pixel: Some(1),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+
+        Ok(Self {
+            id: format!("Image:{index}"),
+            name: multiscale.name.clone(),
+            acquisition_date: None,
+            description: None,
+            instrument_ref: None,
+            pixels: Pixels {
+                id: format!("Pixels:{index}"),
+                dimension_order,
+                pixel_type: pixel_type.to_string(),
+                size_x: size('X'),
+                size_y: size('Y'),
+                size_z: size('Z'),
+                size_c,
+                size_t: size('T'),
+                physical_size_x: physical.remove(&'X'),
+                physical_size_y: physical.remove(&'Y'),
+                physical_size_z: physical.remove(&'Z'),
+                time_increment: physical.remove(&'T'),
+                channels,
+            },
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let px = &self.pixels;
+        let pixels = Element::new("Pixels")
+            .attr("ID", &px.id)
+            .attr("DimensionOrder", px.dimension_order)
+            .attr("Type", &px.pixel_type)
+            .attr("SizeX", px.size_x)
+            .attr("SizeY", px.size_y)
+            .attr("SizeZ", px.size_z)
+            .attr("SizeC", px.size_c)
+            .attr("SizeT", px.size_t)
+            .quantity("PhysicalSizeX", px.physical_size_x.as_ref())
+            .quantity("PhysicalSizeY", px.physical_size_y.as_ref())
+            .quantity("PhysicalSizeZ", px.physical_size_z.as_ref())
+            .quantity("TimeIncrement", px.time_increment.as_ref())
+            .children(px.channels.iter().map(|ch| {
+                Element::new("Channel")
+                    .attr("ID", &ch.id)
+                    .opt_attr("Name", ch.name.as_ref())
+                    .opt_attr("SamplesPerPixel", ch.samples_per_pixel)
+                    .opt_attr("Color", ch.color)
+                    .quantity("EmissionWavelength", ch.emission_wavelength.as_ref())
+                    .quantity("ExcitationWavelength", ch.excitation_wavelength.as_ref())
+                    .opt_attr("Fluor", ch.fluor.as_ref())
+            }))
+            .child(Element::new("MetadataOnly"));
+        Element::new("Image")
+            .attr("ID", &self.id)
+            .opt_attr("Name", self.name.as_ref())
+            .opt_child("AcquisitionDate", self.acquisition_date.as_ref())
+            .opt_child("Description", self.description.as_ref())
+            .children(
+                self.instrument_ref
+                    .iter()
+                    .map(|id| Element::new("InstrumentRef").attr("ID", id)),
+            )
+            .child(pixels)
+    }
+}
+
+impl Instrument {
+    fn to_element(&self) -> Element {
+        Element::new("Instrument")
+            .attr("ID", &self.id)
+            .children(self.microscope.iter().map(|m| {
+                Element::new("Microscope")
+                    .opt_attr("Manufacturer", m.manufacturer.as_ref())
+                    .opt_attr("Model", m.model.as_ref())
+                    .opt_attr("SerialNumber", m.serial_number.as_ref())
+                    .opt_attr("Type", m.r#type.as_ref())
+            }))
+            .children(self.detectors.iter().map(|d| {
+                Element::new("Detector")
+                    .attr("ID", &d.id)
+                    .opt_attr("Manufacturer", d.manufacturer.as_ref())
+                    .opt_attr("Model", d.model.as_ref())
+                    .opt_attr("Type", d.r#type.as_ref())
+            }))
+            .children(self.objectives.iter().map(|o| {
+                Element::new("Objective")
+                    .attr("ID", &o.id)
+                    .opt_attr("Manufacturer", o.manufacturer.as_ref())
+                    .opt_attr("Model", o.model.as_ref())
+                    .opt_attr("NominalMagnification", o.nominal_magnification)
+                    .opt_attr("LensNA", o.lens_na)
+                    .opt_attr("Immersion", o.immersion.as_ref())
+            }))
+    }
+}
+
+impl Plate {
+    /// Describe a plate and its wells, with the given ID index (e.g. `0` for `"Plate:0"`).
+    ///
+    /// Fields of view are numbered in order of the plate's wells and then each well's images,
+    /// and refer to images `"Image:0"`, `"Image:1"`, etc. in that order,
+    /// which must also be described in the document for it to be valid.
+    /// The paths of those image groups (e.g. `"A/1/0"`) are returned alongside the plate,
+    /// suitable for the bioformats2raw `series` field.
+    ///
+    /// Wells without metadata are described without fields of view.
+    pub fn from_plate(
+        index: usize,
+        plate: &v0_5::Plate,
+        wells: &BTreeMap<String, v0_5::Well>,
+    ) -> (Self, Vec<String>) {
+        let mut paths = Vec::default();
+        let mut acquisition_refs: BTreeMap<u64, Vec<String>> = BTreeMap::default();
+        let mut out_wells = Vec::with_capacity(plate.wells.len());
+        for (well_idx, well) in plate.wells.iter().enumerate() {
+            let well_path = well.path.to_string_lossy();
+            let images = wells
+                .get(well_path.as_ref())
+                .map(|w| w.images.as_slice())
+                .unwrap_or_default();
+            let mut samples = Vec::with_capacity(images.len());
+            for img in images {
+                let sample_idx = paths.len();
+                let id = format!("WellSample:{index}:{sample_idx}");
+                if let Some(acq) = img.acquisition {
+                    acquisition_refs.entry(acq).or_default().push(id.clone());
+                }
+                paths.push(format!("{well_path}/{}", img.path.to_string_lossy()));
+                samples.push(WellSample {
+                    id,
+                    index: sample_idx as u64,
+                    image_ref: Some(format!("Image:{sample_idx}")),
+                });
+            }
+            out_wells.push(Well {
+                id: format!("Well:{index}:{well_idx}"),
+                row: well.row_index as u64,
+                column: well.column_index as u64,
+                samples,
+            });
+        }
+
+        let plate_acquisitions = plate
+            .acquisitions
+            .iter()
+            .flatten()
+            .map(|a| PlateAcquisition {
+                id: format!("PlateAcquisition:{}", a.id),
+                name: a.name.clone(),
+                maximum_field_count: a.maximum_field_count.map(|m| m.get()),
+                start_time: None,
+                end_time: None,
+                well_sample_refs: acquisition_refs.remove(&a.id).unwrap_or_default(),
+            })
+            .collect();
+
+        let out = Self {
+            id: format!("Plate:{index}"),
+            name: plate.name.clone(),
+            rows: Some(plate.rows.len() as u64),
+            columns: Some(plate.columns.len() as u64),
+            row_naming_convention: naming_convention(plate.rows.iter().map(|r| r.name.as_str())),
+            column_naming_convention: naming_convention(
+                plate.columns.iter().map(|c| c.name.as_str()),
+            ),
+            wells: out_wells,
+            plate_acquisitions,
+        };
+        (out, paths)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("Plate")
+            .attr("ID", &self.id)
+            .opt_attr("Name", self.name.as_ref())
+            .opt_attr("Rows", self.rows)
+            .opt_attr("Columns", self.columns)
+            .opt_attr("RowNamingConvention", self.row_naming_convention.as_ref())
+            .opt_attr(
+                "ColumnNamingConvention",
+                self.column_naming_convention.as_ref(),
+            )
+            .children(self.wells.iter().map(|w| {
+                Element::new("Well")
+                    .attr("ID", &w.id)
+                    .attr("Row", w.row)
+                    .attr("Column", w.column)
+                    .children(w.samples.iter().map(|s| {
+                        Element::new("WellSample")
+                            .attr("ID", &s.id)
+                            .attr("Index", s.index)
+                            .children(
+                                s.image_ref
+                                    .iter()
+                                    .map(|id| Element::new("ImageRef").attr("ID", id)),
+                            )
+                    }))
+            }))
+            .children(self.plate_acquisitions.iter().map(|a| {
+                Element::new("PlateAcquisition")
+                    .attr("ID", &a.id)
+                    .opt_attr("Name", a.name.as_ref())
+                    .opt_attr("MaximumFieldCount", a.maximum_field_count)
+                    .opt_attr("StartTime", a.start_time.as_ref())
+                    .opt_attr("EndTime", a.end_time.as_ref())
+                    .children(
+                        a.well_sample_refs
+                            .iter()
+                            .map(|id| Element::new("WellSampleRef").attr("ID", id)),
+                    )
+            }))
+    }
+}
+
+/// OME naming convention of a list of row or column names, if they follow one.
+fn naming_convention<'a>(mut names: impl Iterator<Item = &'a str> + Clone) -> Option<String> {
+    if names
+        .clone()
+        .all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    {
+        Some("number".into())
+    } else if names.all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_alphabetic())) {
+        Some("letter".into())
+    } else {
+        None
+    }
+}
+
+/// Minimal XML element builder.
+struct Element {
+    name: &'static str,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: Option<String>,
+}
+
+impl Element {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            attributes: Vec::default(),
+            children: Vec::default(),
+            text: None,
+        }
+    }
+
+    fn attr(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.attributes.push((name.into(), value.to_string()));
+        self
+    }
+
+    fn opt_attr(self, name: &str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(v) => self.attr(name, v),
+            None => self,
+        }
+    }
+
+    /// Add a value attribute along with its `{name}Unit` attribute.
+    fn quantity(self, name: &str, value: Option<&Quantity>) -> Self {
+        match value {
+            Some(q) => self
+                .attr(name, q.value)
+                .attr(format!("{name}Unit"), &q.unit),
+            None => self,
+        }
+    }
+
+    fn child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    fn children(mut self, children: impl IntoIterator<Item = Element>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    fn opt_child(self, name: &'static str, text: Option<&String>) -> Self {
+        match text {
+            Some(t) => self.child(Element {
+                text: Some(t.clone()),
+                ..Element::new(name)
+            }),
+            None => self,
+        }
+    }
+
+    fn write(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        out.push_str(&indent);
+        out.push('<');
+        out.push_str(self.name);
+        for (name, value) in self.attributes.iter() {
+            out.push_str(&format!(" {name}=\"{}\"", escape_xml(value)));
+        }
+        if let Some(text) = self.text.as_ref() {
+            out.push_str(&format!(">{}</{}>\n", escape_xml(text), self.name));
+        } else if self.children.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for child in self.children.iter() {
+                child.write(out, depth + 1);
+            }
+            out.push_str(&format!("{indent}</{}>\n", self.name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_round_trip() {
+        let fields: v0_5::OmeFields = serde_json::from_value(serde_json::json!({
+            "version": "0.5",
+            "multiscales": [{
+                "name": "example",
+                "axes": [
+                    {"name": "t", "type": "time", "unit": "millisecond"},
+                    {"name": "c", "type": "channel"},
+                    {"name": "z", "type": "space", "unit": "micrometer"},
+                    {"name": "y", "type": "space", "unit": "micrometer"},
+                    {"name": "x", "type": "space", "unit": "micrometer"}
+                ],
+                "datasets": [{"path": "0", "coordinateTransformations": [
+                    {"type": "scale", "scale": [100.0, 1.0, 2.0, 0.5, 0.5]}
+                ]}]
+            }],
+            "omero": {"channels": [
+                {"color": "0000FF", "label": "DAPI", "window": {"min": 0.0, "max": 255.0, "start": 0.0, "end": 255.0}},
+                {"color": "00FF00", "label": "GFP", "window": {"min": 0.0, "max": 255.0, "start": 0.0, "end": 255.0}}
+            ]}
+        }))
+        .unwrap();
+        let ome = OmeXml::from_ome_fields(
+            &fields,
+            &[(&[3, 2, 10, 256, 512], "uint8")],
+            &BTreeMap::default(),
+        )
+        .unwrap();
+        let px = &ome.images[0].pixels;
+        assert_eq!(px.dimension_order, DimensionOrder::XYZCT);
+        assert_eq!((px.size_x, px.size_z, px.size_t), (512, 10, 3));
+        assert_eq!(px.physical_size_z.as_ref().unwrap().value, 2.0);
+        assert_eq!(px.time_increment.as_ref().unwrap().unit, "ms");
+        assert_eq!(px.channels[1].name.as_deref(), Some("GFP"));
+        assert_eq!(px.channels[0].rgba(), Some([0, 0, 255, 255]));
+
+        let parsed = OmeXml::parse(&ome.to_xml()).unwrap();
+        assert_eq!(parsed, ome);
+    }
+
+    #[test]
+    fn plate_round_trip() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/ome-zarr/specifications/0.5/examples/plate_strict/plate_6wells.json"
+        ));
+        let meta: v0_5::OmeZarrGroupMetadata = serde_json::from_str(json).unwrap();
+        let plate = meta.attributes.ome.plate.as_ref().unwrap();
+        let well: v0_5::Well = serde_json::from_value(serde_json::json!({"images": [
+            {"path": "0", "acquisition": 1},
+            {"path": "1", "acquisition": 2}
+        ]}))
+        .unwrap();
+        let wells = BTreeMap::from([("A/1".to_string(), well.clone()), ("B/3".to_string(), well)]);
+
+        let (ome_plate, paths) = Plate::from_plate(0, plate, &wells);
+        assert_eq!(paths, ["A/1/0", "A/1/1", "B/3/0", "B/3/1"]);
+        assert_eq!(ome_plate.wells.len(), 6);
+        assert_eq!(ome_plate.row_naming_convention.as_deref(), Some("letter"));
+        assert_eq!(
+            ome_plate.plate_acquisitions[1].well_sample_refs,
+            ["WellSample:0:1", "WellSample:0:3"]
+        );
+
+        let ome = OmeXml::from_ome_fields(&meta.attributes.ome, &[], &wells).unwrap();
+        assert!(ome.images.is_empty());
+        assert_eq!(ome.plates[0].wells, {
+            let mut wells = ome_plate.wells.clone();
+            for sample in wells.iter_mut().flat_map(|w| w.samples.iter_mut()) {
+                sample.image_ref = None;
+            }
+            wells
+        });
+        assert_eq!(OmeXml::parse(&ome.to_xml()).unwrap(), ome);
+
+        let well_fields = v0_5::OmeFields {
+            well: wells.get("A/1").cloned(),
+            ..Default::default()
+        };
+        let ome = OmeXml::from_ome_fields(&well_fields, &[], &BTreeMap::default()).unwrap();
+        assert!(ome.plates.is_empty());
+    }
+
+    #[test]
+    fn image_refs_resolve() {
+        let fields: v0_5::OmeFields = serde_json::from_value(serde_json::json!({
+            "version": "0.5",
+            "multiscales": [{
+                "axes": [
+                    {"name": "y", "type": "space", "unit": "micrometer"},
+                    {"name": "x", "type": "space", "unit": "micrometer"}
+                ],
+                "datasets": [{"path": "0", "coordinateTransformations": [
+                    {"type": "scale", "scale": [0.5, 0.5]}
+                ]}]
+            }],
+            "plate": {
+                "columns": [{"name": "1"}],
+                "rows": [{"name": "A"}],
+                "wells": [{"path": "A/1", "rowIndex": 0, "columnIndex": 0}]
+            }
+        }))
+        .unwrap();
+        let well: v0_5::Well = serde_json::from_value(serde_json::json!({"images": [
+            {"path": "0"},
+            {"path": "1"}
+        ]}))
+        .unwrap();
+        let wells = BTreeMap::from([("A/1".to_string(), well)]);
+        let xml = OmeXml::from_ome_fields(&fields, &[(&[64, 64], "uint8")], &wells)
+            .unwrap()
+            .to_xml();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let ids = |tag: &str| -> Vec<&str> {
+            doc.descendants()
+                .filter(|n| n.has_tag_name(tag))
+                .filter_map(|n| n.attribute("ID"))
+                .collect()
+        };
+        let images = ids("Image");
+        let refs = ids("ImageRef");
+        assert_eq!(images, ["Image:0"]);
+        assert_eq!(refs, ["Image:0"]);
+        assert!(refs.iter().all(|r| images.contains(r)));
+    }
+}
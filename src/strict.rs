@@ -0,0 +1,489 @@
+use serde::{Deserialize, Serialize};
+use validatrix::{Accumulator, Validate};
+
+use crate::{AnyOmeFields, v0_4, v0_5};
+
+type Extra = serde_json::Map<String, serde_json::Value>;
+
+/// Metadata which keeps fields not specified in OME-Zarr in catch-all `extra` maps.
+///
+/// Free-form maps defined by the specification,
+/// such as multiscale `metadata`, label `properties`, and `omero`, are not checked.
+pub trait UnknownFields {
+    /// Add a failure for every field which is not specified in OME-Zarr.
+    fn validate_unknown_fields(&self, accum: &mut Accumulator);
+}
+
+fn unknown(accum: &mut Accumulator, extra: &Extra) {
+    for key in extra.keys() {
+        accum.add_failure(format!("unknown field {key:?}"));
+    }
+}
+
+impl<T: UnknownFields> UnknownFields for Option<T> {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        if let Some(inner) = self {
+            inner.validate_unknown_fields(accum);
+        }
+    }
+}
+
+impl<T: UnknownFields> UnknownFields for Vec<T> {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        for (idx, item) in self.iter().enumerate() {
+            accum.with_key(idx, |a| item.validate_unknown_fields(a));
+        }
+    }
+}
+
+/// Wrapper which additionally fails validation for any fields not specified in OME-Zarr.
+///
+/// By default, unknown fields are kept in each type's `extra` map and ignored by validation.
+/// Deserialise into `Valid<Strict<T>>` to reject them instead.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct Strict<T>(pub T);
+
+impl<T: Validate + UnknownFields> Validate for Strict<T> {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        self.0.validate_inner(accum);
+        self.0.validate_unknown_fields(accum);
+    }
+}
+
+impl UnknownFields for AnyOmeFields {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        match self {
+            AnyOmeFields::V0_4(attrs) => attrs.validate_unknown_fields(accum),
+            AnyOmeFields::V0_5(fields) => {
+                accum.with_key("ome", |a| fields.validate_unknown_fields(a))
+            }
+            #[cfg(feature = "next")]
+            AnyOmeFields::VNext(fields) => {
+                accum.with_key("ome", |a| fields.validate_unknown_fields(a))
+            }
+        }
+    }
+}
+
+impl UnknownFields for v0_4::CoordinateTransform {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        match self {
+            v0_4::CoordinateTransform::Identity { extra } => unknown(accum, extra),
+            v0_4::CoordinateTransform::Translation(
+                v0_4::CoordinateTransformTranslation::List {
+                    translation: _,
+                    extra,
+                }
+                | v0_4::CoordinateTransformTranslation::Path { path: _, extra },
+            )
+            | v0_4::CoordinateTransform::Scale(
+                v0_4::CoordinateTransformScale::List { scale: _, extra }
+                | v0_4::CoordinateTransformScale::Path { path: _, extra },
+            ) => unknown(accum, extra),
+        }
+    }
+}
+
+impl UnknownFields for v0_4::Axis {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            name: _,
+            r#type: _,
+            unit: _,
+            extra,
+        } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::MultiscaleImageDataset {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            path: _,
+            coordinate_transformations,
+            extra,
+        } = self;
+        accum.with_key("coordinateTransformations", |a| {
+            coordinate_transformations.validate_unknown_fields(a)
+        });
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::MultiscaleImage {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            version: _,
+            name: _,
+            axes,
+            datasets,
+            coordinate_transformations,
+            r#type: _,
+            metadata: _,
+            extra,
+        } = self;
+        accum.with_key("axes", |a| axes.validate_unknown_fields(a));
+        accum.with_key("datasets", |a| datasets.validate_unknown_fields(a));
+        accum.with_key("coordinateTransformations", |a| {
+            coordinate_transformations.validate_unknown_fields(a)
+        });
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_5::MultiscaleImage {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            name: _,
+            axes,
+            datasets,
+            coordinate_transformations,
+            r#type: _,
+            metadata: _,
+            extra,
+        } = self;
+        accum.with_key("axes", |a| axes.validate_unknown_fields(a));
+        accum.with_key("datasets", |a| datasets.validate_unknown_fields(a));
+        accum.with_key("coordinateTransformations", |a| {
+            coordinate_transformations.validate_unknown_fields(a)
+        });
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::ImageLabelColor {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            label_value: _,
+            rgba: _,
+            extra,
+        } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::ImageLabelSource {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self { image: _, extra } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::ImageLabel {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            version: _,
+            colors,
+            properties: _,
+            source,
+            extra,
+        } = self;
+        accum.with_key("colors", |a| colors.validate_unknown_fields(a));
+        accum.with_key("source", |a| source.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_5::ImageLabel {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            colors,
+            properties: _,
+            source,
+            extra,
+        } = self;
+        accum.with_key("colors", |a| colors.validate_unknown_fields(a));
+        accum.with_key("source", |a| source.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::PlateAcquisition {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            id: _,
+            name: _,
+            maximum_field_count: _,
+            description: _,
+            start_time: _,
+            end_time: _,
+            extra,
+        } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::PlateColumn {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self { name: _, extra } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::PlateRow {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self { name: _, extra } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::PlateWell {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            path: _,
+            row_index: _,
+            column_index: _,
+            extra,
+        } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::Plate {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            version: _,
+            acquisitions,
+            columns,
+            field_count: _,
+            name: _,
+            rows,
+            wells,
+            extra,
+        } = self;
+        accum.with_key("acquisitions", |a| acquisitions.validate_unknown_fields(a));
+        accum.with_key("columns", |a| columns.validate_unknown_fields(a));
+        accum.with_key("rows", |a| rows.validate_unknown_fields(a));
+        accum.with_key("wells", |a| wells.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_5::Plate {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            acquisitions,
+            columns,
+            field_count: _,
+            name: _,
+            rows,
+            wells,
+            extra,
+        } = self;
+        accum.with_key("acquisitions", |a| acquisitions.validate_unknown_fields(a));
+        accum.with_key("columns", |a| columns.validate_unknown_fields(a));
+        accum.with_key("rows", |a| rows.validate_unknown_fields(a));
+        accum.with_key("wells", |a| wells.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::WellImage {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            path: _,
+            acquisition: _,
+            extra,
+        } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::Well {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            version: _,
+            images,
+            extra,
+        } = self;
+        accum.with_key("images", |a| images.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_5::Well {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self { images, extra } = self;
+        accum.with_key("images", |a| images.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::OmeNgffGroupAttributes {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            bioformats2raw: _,
+            multiscales,
+            labels: _,
+            image_label,
+            plate,
+            well,
+            omero: _,
+            extra,
+        } = self;
+        accum.with_key("multiscales", |a| multiscales.validate_unknown_fields(a));
+        accum.with_key("imageLabel", |a| image_label.validate_unknown_fields(a));
+        accum.with_key("plate", |a| plate.validate_unknown_fields(a));
+        accum.with_key("well", |a| well.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::ZGroup {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            zarr_format: _,
+            extra,
+        } = self;
+        unknown(accum, extra);
+    }
+}
+
+impl UnknownFields for v0_4::OmeNgffGroup {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self { zgroup, attributes } = self;
+        accum.with_key("zgroup", |a| zgroup.validate_unknown_fields(a));
+        accum.with_key("attributes", |a| attributes.validate_unknown_fields(a));
+    }
+}
+
+impl UnknownFields for v0_5::OmeFields {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            bioformats2raw: _,
+            version: _,
+            multiscales,
+            labels: _,
+            image_label,
+            plate,
+            well,
+            omero: _,
+            extra,
+        } = self;
+        accum.with_key("multiscales", |a| multiscales.validate_unknown_fields(a));
+        accum.with_key("imageLabel", |a| image_label.validate_unknown_fields(a));
+        accum.with_key("plate", |a| plate.validate_unknown_fields(a));
+        accum.with_key("well", |a| well.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+/// Attributes other than `ome` belong to other namespaces, so are not checked.
+impl<E> UnknownFields for v0_5::OmeZarrGroupAttributes<E> {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self { ome, extensions: _ } = self;
+        accum.with_key("ome", |a| ome.validate_unknown_fields(a));
+    }
+}
+
+impl<E> UnknownFields for v0_5::OmeZarrGroupMetadata<E> {
+    fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+        let Self {
+            zarr_format: _,
+            node_type: _,
+            attributes,
+            extra,
+        } = self;
+        accum.with_key("attributes", |a| attributes.validate_unknown_fields(a));
+        unknown(accum, extra);
+    }
+}
+
+#[cfg(feature = "next")]
+mod next_impls {
+    use validatrix::Accumulator;
+
+    use super::{UnknownFields, unknown};
+    use crate::next;
+
+    impl UnknownFields for next::OmeFields {
+        fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+            let Self {
+                bioformats2raw: _,
+                version: _,
+                multiscales,
+                labels: _,
+                image_label,
+                plate,
+                well,
+                omero: _,
+                extra,
+            } = self;
+            accum.with_key("multiscales", |a| multiscales.validate_unknown_fields(a));
+            accum.with_key("imageLabel", |a| image_label.validate_unknown_fields(a));
+            accum.with_key("plate", |a| plate.validate_unknown_fields(a));
+            accum.with_key("well", |a| well.validate_unknown_fields(a));
+            unknown(accum, extra);
+        }
+    }
+
+    /// Attributes other than `ome` belong to other namespaces, so are not checked.
+    impl<E> UnknownFields for next::OmeZarrGroupAttributes<E> {
+        fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+            let Self { ome, extensions: _ } = self;
+            accum.with_key("ome", |a| ome.validate_unknown_fields(a));
+        }
+    }
+
+    impl<E> UnknownFields for next::OmeZarrGroupMetadata<E> {
+        fn validate_unknown_fields(&self, accum: &mut Accumulator) {
+            let Self {
+                zarr_format: _,
+                node_type: _,
+                attributes,
+                extra,
+            } = self;
+            accum.with_key("attributes", |a| attributes.validate_unknown_fields(a));
+            unknown(accum, extra);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use validatrix::Valid;
+
+    use super::*;
+
+    fn metadata(axis: serde_json::Value, transform: serde_json::Value) -> serde_json::Value {
+        json!({
+            "zarr_format": 3,
+            "node_type": "group",
+            "attributes": {
+                "ome": {
+                    "version": "0.5",
+                    "multiscales": [{
+                        "axes": [{"name": "y", "type": "space"}, axis],
+                        "datasets": [{"path": "0", "coordinateTransformations": [transform]}],
+                    }],
+                },
+                "vendor": {"key": "value"},
+            },
+        })
+    }
+
+    #[test]
+    fn known_fields_pass() {
+        let value = metadata(
+            json!({"name": "x", "type": "space"}),
+            json!({"type": "scale", "scale": [1.0, 1.0]}),
+        );
+        let _: Valid<Strict<v0_5::OmeZarrGroupMetadata>> = serde_json::from_value(value).unwrap();
+    }
+
+    #[test]
+    fn unknown_fields_fail() {
+        let value = metadata(
+            json!({"name": "x", "type": "space", "orientation": "left-to-right"}),
+            json!({"type": "scale", "scale": [1.0, 1.0], "units": "um"}),
+        );
+
+        let lenient: Valid<v0_5::OmeZarrGroupMetadata> =
+            serde_json::from_value(value.clone()).unwrap();
+        let strict = Strict(lenient.into_inner());
+        let err = Validate::validate(&strict).unwrap_err();
+        assert_eq!(err.len(), 2);
+    }
+}
@@ -1,6 +1,9 @@
 pub(crate) mod axes;
 pub(crate) mod bioformats2raw_layout;
 pub(crate) mod coordinate_transformations;
+pub(crate) mod group;
+pub(crate) mod label_colors;
+pub(crate) mod label_properties;
 pub(crate) mod labels;
 pub(crate) mod multiscales;
 pub(crate) mod omero;
@@ -10,6 +13,9 @@ pub(crate) mod well;
 pub use axes::*;
 pub use bioformats2raw_layout::*;
 pub use coordinate_transformations::*;
+pub use group::*;
+pub use label_colors::*;
+pub use label_properties::*;
 pub use labels::*;
 pub use multiscales::*;
 pub use omero::*;
@@ -32,7 +38,7 @@ crate::constrained_version!(ConstrainedVersion, "==0.4", "0.4");
 ///
 /// Has aliases [OmeFields] and [OmeZarrGroupAttributes] for consistency with later versions.
 /// In later versions, the OME fields are namespaced and so those two types refer to different things.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct OmeNgffGroupAttributes {
     /// Transitional `bioformats2raw.layout` metadata.
     #[serde(
@@ -59,6 +65,9 @@ pub struct OmeNgffGroupAttributes {
     /// Transitional OMERO metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub omero: Option<Omero>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl OmeNgffGroupAttributes {
@@ -89,6 +98,11 @@ impl OmeNgffGroupAttributes {
 
 impl Validate for OmeNgffGroupAttributes {
     fn validate_inner(&self, accum: &mut Accumulator) {
+        // fields are flattened into this object
+        if let Some(b) = self.bioformats2raw.as_ref() {
+            b.validate_inner(accum);
+        }
+
         if let Some(m) = self.multiscales.as_ref() {
             accum.with_key("multiscales", |a| {
                 if m.is_empty() {
@@ -106,6 +120,10 @@ impl Validate for OmeNgffGroupAttributes {
             accum.validate_member_at("plate", p);
         }
 
+        if let Some(w) = self.well.as_ref() {
+            accum.validate_member_at("well", w);
+        }
+
         if let Some(o) = self.omero.as_ref() {
             accum.validate_member_at("omero", o);
         }
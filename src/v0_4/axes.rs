@@ -6,8 +6,7 @@ use serde::{Deserialize, Serialize};
 use validatrix::{Accumulator, Validate};
 
 /// `axis` element metadata. Represents a dimension (axis) of a physical coordinate space.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Axis {
     /// The name for this dimension.
     pub name: String,
@@ -17,6 +16,9 @@ pub struct Axis {
     /// The optional physical unit of this dimension.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unit: Option<AxisUnit>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for Axis {
@@ -58,7 +60,7 @@ pub enum AxisType {
 
 /// [`Axis`] `unit` metadata. Represents the unit of an axis.
 #[non_exhaustive]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(untagged)]
 pub enum AxisUnit {
     /// A recognised `space` axis unit.
@@ -84,7 +86,7 @@ impl From<AxisUnitTime> for AxisUnit {
 /// [`AxisUnit`] physical `space` units valid according to UDUNITS-2.
 #[non_exhaustive]
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum AxisUnitSpace {
     Angstrom,
@@ -118,7 +120,7 @@ pub enum AxisUnitSpace {
 /// [`AxisUnit`] physical `time` units valid according to UDUNITS-2.
 #[non_exhaustive]
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum AxisUnitTime {
     Attosecond,
@@ -146,6 +148,71 @@ pub enum AxisUnitTime {
     Zettasecond,
 }
 
+impl AxisUnitSpace {
+    /// Length of one of this unit, in meters.
+    pub fn in_meters(&self) -> f64 {
+        match self {
+            Self::Angstrom => 1e-10,
+            Self::Attometer => 1e-18,
+            Self::Centimeter => 1e-2,
+            Self::Decimeter => 1e-1,
+            Self::Exameter => 1e18,
+            Self::Femtometer => 1e-15,
+            Self::Foot => 0.3048,
+            Self::Gigameter => 1e9,
+            Self::Hectometer => 1e2,
+            Self::Inch => 0.0254,
+            Self::Kilometer => 1e3,
+            Self::Megameter => 1e6,
+            Self::Meter => 1.0,
+            Self::Micrometer => 1e-6,
+            Self::Mile => 1609.344,
+            Self::Millimeter => 1e-3,
+            Self::Nanometer => 1e-9,
+            Self::Parsec => 3.085_677_581_491_367e16,
+            Self::Petameter => 1e15,
+            Self::Picometer => 1e-12,
+            Self::Terameter => 1e12,
+            Self::Yard => 0.9144,
+            Self::Yoctometer => 1e-24,
+            Self::Yottameter => 1e24,
+            Self::Zeptometer => 1e-21,
+            Self::Zettameter => 1e21,
+        }
+    }
+}
+
+impl AxisUnitTime {
+    /// Duration of one of this unit, in seconds.
+    pub fn in_seconds(&self) -> f64 {
+        match self {
+            Self::Attosecond => 1e-18,
+            Self::Centisecond => 1e-2,
+            Self::Day => 86_400.0,
+            Self::Decisecond => 1e-1,
+            Self::Exasecond => 1e18,
+            Self::Femtosecond => 1e-15,
+            Self::Gigasecond => 1e9,
+            Self::Hectosecond => 1e2,
+            Self::Hour => 3_600.0,
+            Self::Kilosecond => 1e3,
+            Self::Megasecond => 1e6,
+            Self::Microsecond => 1e-6,
+            Self::Millisecond => 1e-3,
+            Self::Minute => 60.0,
+            Self::Nanosecond => 1e-9,
+            Self::Petasecond => 1e15,
+            Self::Picosecond => 1e-12,
+            Self::Second => 1.0,
+            Self::Terasecond => 1e12,
+            Self::Yoctosecond => 1e-24,
+            Self::Yottasecond => 1e24,
+            Self::Zeptosecond => 1e-21,
+            Self::Zettasecond => 1e21,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,18 +2,114 @@
 //!
 //! <https://ngff.openmicroscopy.org/0.4/#bf2raw>.
 
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
+use validatrix::{Accumulator, Validate};
+
+use crate::{HierarchyGroup, resolve_path};
 
 /// Top level group metadata indicating with the transitional `bioformats2raw.layout` metadata.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Bioformats2Raw {
     /// The top-level identifier metadata added by bioformats2raw
     #[serde(rename = "bioformats2raw.layout")]
     pub layout: monostate::MustBe!(3u64),
     /// Paths to image groups
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub series: Option<Vec<String>>,
 }
 
+impl Validate for Bioformats2Raw {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        let Some(series) = self.series.as_ref() else {
+            return;
+        };
+        accum.with_key("series", |a| {
+            if series.is_empty() {
+                a.add_failure("empty series");
+            }
+            let mut visited = HashSet::with_capacity(series.len());
+            for (idx, path) in series.iter().enumerate() {
+                if path.is_empty() || path.starts_with('/') {
+                    a.add_failure_at(idx, format!("not a relative path: {path:?}"));
+                } else if resolve_path("", path).is_none() {
+                    a.add_failure_at(idx, format!("path is outside the hierarchy: {path}"));
+                }
+                if !visited.insert(path) {
+                    a.add_failure_at(idx, format!("not unique: {path}"));
+                }
+            }
+        });
+    }
+}
+
+impl Bioformats2Raw {
+    /// Paths of the image groups of each series, relative to the root of the hierarchy.
+    ///
+    /// If `series` is given, its paths are resolved relative to the group.
+    /// Otherwise, the images are in consecutively numbered groups (`"0"`, `"1"`, ...),
+    /// and paths are yielded for as long as those groups exist in the hierarchy.
+    /// Paths which are outside the hierarchy are skipped.
+    pub fn series_paths<'a, T>(
+        &'a self,
+        group: &HierarchyGroup<'a, T>,
+    ) -> Box<dyn Iterator<Item = String> + 'a> {
+        let base = group.path;
+        match self.series.as_ref() {
+            Some(series) => Box::new(series.iter().filter_map(move |p| resolve_path(base, p))),
+            None => {
+                let hierarchy = group.hierarchy;
+                Box::new(
+                    (0..)
+                        .map_while(move |idx: usize| resolve_path(base, &idx.to_string()))
+                        .take_while(move |p| hierarchy.get(p).is_some()),
+                )
+            }
+        }
+    }
+}
+
+/// Check that each series of a bioformats2raw group resolves to an image group in the hierarchy.
+///
+/// `is_image` decides whether a group's metadata describes a multiscale image.
+/// Images stored in a plate hierarchy are checked by the plate, so if `has_plate`,
+/// the numbered-path fallback is not required to find any images.
+pub(crate) fn validate_series_in_hierarchy<T>(
+    accum: &mut Accumulator,
+    layout: &Bioformats2Raw,
+    group: &HierarchyGroup<'_, T>,
+    has_plate: bool,
+    is_image: impl Fn(&T) -> bool,
+) {
+    let check = |a: &mut Accumulator, path: &str| match group.hierarchy.get(path) {
+        None => a.add_failure(format!("no group at {path:?}")),
+        Some(m) if !is_image(m) => a.add_failure(format!("group at {path:?} is not an image")),
+        Some(_) => (),
+    };
+
+    let Some(series) = layout.series.as_ref() else {
+        let mut found = false;
+        for path in layout.series_paths(group) {
+            found = true;
+            accum.with_key("series", |a| check(a, &path));
+        }
+        if !found && !has_plate {
+            accum.add_failure("no series given, and no image at numbered path \"0\"");
+        }
+        return;
+    };
+
+    accum.with_key("series", |acc| {
+        for (idx, relative) in series.iter().enumerate() {
+            // paths outside the hierarchy are reported by the layout's own validation
+            if let Some(path) = resolve_path(group.path, relative) {
+                acc.with_key(idx, |a| check(a, &path));
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use crate::v0_4::OmeNgffGroupAttributes;
@@ -39,4 +135,17 @@ mod tests {
         let ome_metadata: OmeNgffGroupAttributes = serde_json::from_str(json).unwrap();
         let _bioformats2raw: Bioformats2Raw = ome_metadata.bioformats2raw.unwrap();
     }
+
+    #[test]
+    fn invalid_series() {
+        let layout: Bioformats2Raw = serde_json::from_str(
+            r#"{"bioformats2raw.layout": 3, "series": ["0", "0", "/abs", "../up"]}"#,
+        )
+        .unwrap();
+        assert_eq!(layout.validate().unwrap_err().len(), 3);
+
+        let layout: Bioformats2Raw =
+            serde_json::from_str(r#"{"bioformats2raw.layout": 3, "series": []}"#).unwrap();
+        assert_eq!(layout.validate().unwrap_err().len(), 1);
+    }
 }
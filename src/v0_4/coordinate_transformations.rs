@@ -7,16 +7,20 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use validatrix::{Accumulator, Validate};
 
-use crate::MaybeNDim;
+use crate::{Error, MaybeNDim};
 
 /// `coordinate_transformations` element metadata. Represents a single coordinate transformation.
 ///
 /// It must contain the field "type".
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum CoordinateTransform {
     /// The identity transformation.
-    Identity,
+    Identity {
+        /// Catch-all field for any fields not specified in OME-Zarr.
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
     /// A translation vector.
     Translation(CoordinateTransformTranslation),
     /// A scale vector.
@@ -25,7 +29,7 @@ pub enum CoordinateTransform {
 
 impl Validate for CoordinateTransform {
     fn validate_inner(&self, accum: &mut Accumulator) {
-        if let CoordinateTransform::Identity = self {
+        if let CoordinateTransform::Identity { .. } = self {
             accum.add_failure("identity transform cannot be used here");
         }
     }
@@ -34,27 +38,83 @@ impl Validate for CoordinateTransform {
 impl MaybeNDim for CoordinateTransform {
     fn maybe_ndim(&self) -> Option<usize> {
         match self {
-            CoordinateTransform::Identity => None,
+            CoordinateTransform::Identity { .. } => None,
             CoordinateTransform::Translation(t) => t.maybe_ndim(),
             CoordinateTransform::Scale(t) => t.maybe_ndim(),
         }
     }
 }
 
+/// Collapse a sequence of transformations, applied in order, into a single scale and translation
+/// such that `physical = scale * index + translation` for each axis.
+pub(crate) fn compose_transforms<'a>(
+    ndim: usize,
+    cts: impl IntoIterator<Item = &'a CoordinateTransform>,
+) -> crate::Result<(Vec<f64>, Vec<f64>)> {
+    let mut scale = vec![1.0; ndim];
+    let mut translation = vec![0.0; ndim];
+    for ct in cts {
+        match ct {
+            CoordinateTransform::Identity { .. } => (),
+            CoordinateTransform::Scale(CoordinateTransformScale::List { scale: s, .. }) => {
+                check_ndim(ndim, s.len())?;
+                for ((sc, tr), s) in scale.iter_mut().zip(translation.iter_mut()).zip(s) {
+                    *sc *= s;
+                    *tr *= s;
+                }
+            }
+            CoordinateTransform::Translation(CoordinateTransformTranslation::List {
+                translation: t,
+                ..
+            }) => {
+                check_ndim(ndim, t.len())?;
+                for (tr, t) in translation.iter_mut().zip(t) {
+                    *tr += t;
+                }
+            }
+            CoordinateTransform::Scale(CoordinateTransformScale::Path { path, .. })
+            | CoordinateTransform::Translation(CoordinateTransformTranslation::Path {
+                path, ..
+            }) => {
+                return Err(Error::PathTransform(path.clone()));
+            }
+        }
+    }
+    Ok((scale, translation))
+}
+
+fn check_ndim(expected: usize, actual: usize) -> crate::Result<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::NDimMismatch { expected, actual })
+    }
+}
+
 /// [`CoordinateTransform`] `translation` type metadata.
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum CoordinateTransformTranslation {
     /// A list of floats.
-    List { translation: Vec<f32> },
+    List {
+        translation: Vec<f64>,
+        /// Catch-all field for any fields not specified in OME-Zarr.
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
     /// A path to binary data at a location in this container.
-    Path { path: PathBuf },
+    Path {
+        path: PathBuf,
+        /// Catch-all field for any fields not specified in OME-Zarr.
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
 }
 
 impl MaybeNDim for CoordinateTransformTranslation {
     fn maybe_ndim(&self) -> Option<usize> {
-        if let CoordinateTransformTranslation::List { translation } = self {
+        if let CoordinateTransformTranslation::List { translation, .. } = self {
             Some(translation.len())
         } else {
             None
@@ -62,32 +122,48 @@ impl MaybeNDim for CoordinateTransformTranslation {
     }
 }
 
-impl From<Vec<f32>> for CoordinateTransformTranslation {
-    fn from(translation: Vec<f32>) -> Self {
-        CoordinateTransformTranslation::List { translation }
+impl From<Vec<f64>> for CoordinateTransformTranslation {
+    fn from(translation: Vec<f64>) -> Self {
+        CoordinateTransformTranslation::List {
+            translation,
+            extra: Default::default(),
+        }
     }
 }
 
 impl From<PathBuf> for CoordinateTransformTranslation {
     fn from(path: PathBuf) -> Self {
-        CoordinateTransformTranslation::Path { path }
+        CoordinateTransformTranslation::Path {
+            path,
+            extra: Default::default(),
+        }
     }
 }
 
 /// [`CoordinateTransform`] `scale` type metadata.
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum CoordinateTransformScale {
     /// A list of floats.
-    List { scale: Vec<f32> },
+    List {
+        scale: Vec<f64>,
+        /// Catch-all field for any fields not specified in OME-Zarr.
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
     /// A path to binary data at a location in this container.
-    Path { path: PathBuf },
+    Path {
+        path: PathBuf,
+        /// Catch-all field for any fields not specified in OME-Zarr.
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
 }
 
 impl MaybeNDim for CoordinateTransformScale {
     fn maybe_ndim(&self) -> Option<usize> {
-        if let CoordinateTransformScale::List { scale } = self {
+        if let CoordinateTransformScale::List { scale, .. } = self {
             Some(scale.len())
         } else {
             None
@@ -95,14 +171,49 @@ impl MaybeNDim for CoordinateTransformScale {
     }
 }
 
-impl From<Vec<f32>> for CoordinateTransformScale {
-    fn from(scale: Vec<f32>) -> Self {
-        CoordinateTransformScale::List { scale }
+impl From<Vec<f64>> for CoordinateTransformScale {
+    fn from(scale: Vec<f64>) -> Self {
+        CoordinateTransformScale::List {
+            scale,
+            extra: Default::default(),
+        }
     }
 }
 
 impl From<PathBuf> for CoordinateTransformScale {
     fn from(path: PathBuf) -> Self {
-        CoordinateTransformScale::Path { path }
+        CoordinateTransformScale::Path {
+            path,
+            extra: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_roundtrip() {
+        // sub-micrometer pixel sizes and centimeter-scale offsets at nanometer precision
+        let json = r#"[{"type":"scale","scale":[0.1083333,0.1083333,1e-9]},{"type":"translation","translation":[12345.678901234,-0.000123456789,1.7976931348623157e+308]}]"#;
+        let cts: Vec<CoordinateTransform> = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&cts).unwrap(), json);
+
+        let (scale, translation) = compose_transforms(3, &cts).unwrap();
+        assert_eq!(scale, vec![0.1083333, 0.1083333, 1e-9]);
+        assert_eq!(translation[0], 12345.678901234);
+    }
+
+    #[test]
+    fn unknown_fields_roundtrip() {
+        let json = r#"[{"type":"identity","note":"unused"},{"type":"scale","scale":[0.5,0.5],"fiji:origin":"top-left"},{"type":"translation","path":"offsets","units":["um","um"]}]"#;
+        let cts: Vec<CoordinateTransform> = serde_json::from_str(json).unwrap();
+        let CoordinateTransform::Scale(CoordinateTransformScale::List { extra, .. }) = &cts[1]
+        else {
+            panic!("expected scale list")
+        };
+        assert_eq!(extra["fiji:origin"], "top-left");
+        assert_eq!(serde_json::to_string(&cts).unwrap(), json);
     }
 }
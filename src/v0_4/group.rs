@@ -0,0 +1,128 @@
+//! Zarr v2 group metadata (`.zgroup` and `.zattrs`).
+//!
+//! <https://ngff.openmicroscopy.org/0.4/#on-disk>.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use validatrix::{Accumulator, Validate};
+
+use super::OmeNgffGroupAttributes;
+
+/// Zarr format version of OME-NGFF 0.4 groups.
+pub const ZARR_FORMAT: u64 = 2;
+
+/// Name of the file containing a Zarr v2 group's core metadata.
+pub const ZGROUP_PATH: &str = ".zgroup";
+
+/// Name of the file containing a Zarr v2 node's user attributes.
+pub const ZATTRS_PATH: &str = ".zattrs";
+
+/// Contents of a Zarr v2 group's `.zgroup` file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ZGroup {
+    /// Zarr format version; must be [ZARR_FORMAT].
+    pub zarr_format: u64,
+    /// Catch-all field for any fields not specified in Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for ZGroup {
+    fn default() -> Self {
+        Self {
+            zarr_format: ZARR_FORMAT,
+            extra: Default::default(),
+        }
+    }
+}
+
+impl Validate for ZGroup {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        if self.zarr_format != ZARR_FORMAT {
+            accum.add_failure_at(
+                "zarr_format",
+                format!(
+                    "got Zarr format {}, expected {ZARR_FORMAT}",
+                    self.zarr_format
+                ),
+            );
+        }
+    }
+}
+
+/// An OME-NGFF 0.4 group: its `.zgroup` core metadata and `.zattrs` user attributes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OmeNgffGroup {
+    /// Contents of `.zgroup`.
+    pub zgroup: ZGroup,
+    /// Contents of `.zattrs`.
+    pub attributes: OmeNgffGroupAttributes,
+}
+
+impl Validate for OmeNgffGroup {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        accum.validate_member_at("zgroup", &self.zgroup);
+        accum.validate_member_at("attributes", &self.attributes);
+    }
+}
+
+impl OmeNgffGroup {
+    /// Parse a group from the contents of its `.zgroup` and `.zattrs` files.
+    ///
+    /// A group with no `.zattrs` has no attributes.
+    ///
+    /// # Errors
+    /// Returns an error if either document cannot be deserialised.
+    pub fn from_json(zgroup: &str, zattrs: Option<&str>) -> crate::Result<Self> {
+        Ok(Self {
+            zgroup: serde_json::from_str(zgroup)?,
+            attributes: zattrs
+                .map(serde_json::from_str)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Read a group from the `.zgroup` and `.zattrs` files in a directory.
+    ///
+    /// # Errors
+    /// Returns an error if `.zgroup` does not exist,
+    /// if either file cannot be read, or see [OmeNgffGroup::from_json].
+    pub fn read(dir: impl AsRef<Path>) -> crate::Result<Self> {
+        let dir = dir.as_ref();
+        let zgroup = std::fs::read_to_string(dir.join(ZGROUP_PATH))?;
+        let zattrs = match std::fs::read_to_string(dir.join(ZATTRS_PATH)) {
+            Ok(s) => Some(s),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        Self::from_json(&zgroup, zattrs.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_group() {
+        let dir = std::env::temp_dir().join(format!(
+            "ome_zarr_metadata_v0_4_group_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(ZGROUP_PATH), r#"{"zarr_format": 2}"#).unwrap();
+        std::fs::write(dir.join(ZATTRS_PATH), r#"{"labels": ["cells"]}"#).unwrap();
+        let group = OmeNgffGroup::read(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let group = group.unwrap();
+        group.validate().unwrap();
+        assert_eq!(group.attributes.labels.unwrap(), vec!["cells".to_string()]);
+
+        let group = OmeNgffGroup::from_json(r#"{"zarr_format": 3}"#, None).unwrap();
+        assert!(group.attributes.labels.is_none());
+        assert!(group.validate().is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! Lookup and generation of label colors.
+
+use std::collections::{HashMap, HashSet};
+
+use super::ImageLabelColor;
+
+/// Indexed lookup from label value to RGBA color.
+///
+/// Build one by collecting an [`ImageLabel`](super::ImageLabel)'s `colors`,
+/// or from a generated palette with [Palette::generate].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelColorTable {
+    colors: HashMap<u64, [u8; 4]>,
+    default: Option<[u8; 4]>,
+}
+
+impl LabelColorTable {
+    /// Use the given color for any label not listed in the table.
+    pub fn with_default(mut self, rgba: [u8; 4]) -> Self {
+        self.default = Some(rgba);
+        self
+    }
+
+    /// The color used for labels not listed in the table, if any.
+    pub fn default_color(&self) -> Option<[u8; 4]> {
+        self.default
+    }
+
+    /// Color of the given label, falling back to the default color if one was set.
+    pub fn get(&self, label_value: u64) -> Option<[u8; 4]> {
+        self.colors.get(&label_value).copied().or(self.default)
+    }
+
+    /// Whether the given label is listed in the table (ignoring the default color).
+    pub fn contains(&self, label_value: u64) -> bool {
+        self.colors.contains_key(&label_value)
+    }
+
+    /// Set the color of a label, returning its previous color if it was listed.
+    pub fn insert(&mut self, label_value: u64, rgba: [u8; 4]) -> Option<[u8; 4]> {
+        self.colors.insert(label_value, rgba)
+    }
+
+    /// Number of listed labels.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Whether no labels are listed.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Listed colors as `colors` metadata, sorted by label value.
+    pub fn to_colors(&self) -> Vec<ImageLabelColor> {
+        let mut out: Vec<_> = self
+            .colors
+            .iter()
+            .map(|(label_value, rgba)| ImageLabelColor {
+                label_value: *label_value,
+                rgba: *rgba,
+                extra: Default::default(),
+            })
+            .collect();
+        out.sort_by_key(|c| c.label_value);
+        out
+    }
+}
+
+/// If a label value is repeated, the first color is used.
+impl<'a> FromIterator<&'a ImageLabelColor> for LabelColorTable {
+    fn from_iter<I: IntoIterator<Item = &'a ImageLabelColor>>(iter: I) -> Self {
+        let mut colors = HashMap::default();
+        for c in iter {
+            colors.entry(c.label_value).or_insert(c.rgba);
+        }
+        Self {
+            colors,
+            default: None,
+        }
+    }
+}
+
+/// Strategy for generating distinct colors for categorical labels.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Palette {
+    /// Hues spaced by the golden ratio, at fixed saturation and value.
+    ///
+    /// Cheap, and adjacent labels have very different hues.
+    GoldenRatio,
+    /// Glasbey-style palette: each color is chosen from a random pool
+    /// to be as perceptually distinct (in CIELAB space) as possible from all previous colors,
+    /// and from black and white.
+    Glasbey,
+}
+
+impl Palette {
+    /// Generate opaque colors for the given label values.
+    ///
+    /// The output is reproducible for a given seed.
+    /// Repeated label values are skipped, so the output is valid `colors` metadata.
+    pub fn generate(
+        &self,
+        label_values: impl IntoIterator<Item = u64>,
+        seed: u64,
+    ) -> Vec<ImageLabelColor> {
+        let mut seen = HashSet::new();
+        let labels: Vec<u64> = label_values
+            .into_iter()
+            .filter(|l| seen.insert(*l))
+            .collect();
+        let mut rng = SplitMix64(seed);
+        let rgbs = match self {
+            Palette::GoldenRatio => golden_ratio(labels.len(), &mut rng),
+            Palette::Glasbey => glasbey(labels.len(), &mut rng),
+        };
+        labels
+            .into_iter()
+            .zip(rgbs)
+            .map(|(label_value, [r, g, b])| ImageLabelColor {
+                label_value,
+                rgba: [r, g, b, u8::MAX],
+                extra: Default::default(),
+            })
+            .collect()
+    }
+
+    /// Generate a [LabelColorTable] for the given label values; see [Palette::generate].
+    pub fn generate_table(
+        &self,
+        label_values: impl IntoIterator<Item = u64>,
+        seed: u64,
+    ) -> LabelColorTable {
+        self.generate(label_values, seed).iter().collect()
+    }
+}
+
+const GOLDEN_RATIO_CONJUGATE: f64 = 0.618_033_988_749_895;
+
+fn golden_ratio(n: usize, rng: &mut SplitMix64) -> Vec<[u8; 3]> {
+    let mut hue = rng.next_f64();
+    (0..n)
+        .map(|_| {
+            let rgb = hsv_to_rgb(hue, 0.65, 0.95);
+            hue = (hue + GOLDEN_RATIO_CONJUGATE).fract();
+            rgb
+        })
+        .collect()
+}
+
+/// Minimum number of random candidates considered by the Glasbey palette.
+const GLASBEY_MIN_CANDIDATES: usize = 4096;
+
+fn glasbey(n: usize, rng: &mut SplitMix64) -> Vec<[u8; 3]> {
+    let n_candidates = GLASBEY_MIN_CANDIDATES.max(n * 8);
+    let mut candidates: Vec<([u8; 3], [f64; 3])> = (0..n_candidates)
+        .map(|_| {
+            let rgb = rng.next_rgb();
+            (rgb, srgb_to_lab(rgb))
+        })
+        .collect();
+
+    // squared distance from each candidate to its nearest chosen color
+    let mut nearest = vec![f64::INFINITY; candidates.len()];
+    for avoid in [[0, 0, 0], [u8::MAX; 3]] {
+        update_nearest(&candidates, &mut nearest, srgb_to_lab(avoid));
+    }
+
+    let mut out = Vec::with_capacity(n);
+    for _ in 0..n {
+        // there are always more candidates than labels
+        let Some((idx, _)) = nearest
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        else {
+            break;
+        };
+        let (rgb, lab) = candidates.swap_remove(idx);
+        nearest.swap_remove(idx);
+        update_nearest(&candidates, &mut nearest, lab);
+        out.push(rgb);
+    }
+    out
+}
+
+fn update_nearest(candidates: &[([u8; 3], [f64; 3])], nearest: &mut [f64], lab: [f64; 3]) {
+    for ((_, c), d) in candidates.iter().zip(nearest.iter_mut()) {
+        *d = d.min(lab_distance_sq(*c, lab));
+    }
+}
+
+/// Small, seedable pseudo-random number generator so that palettes are reproducible
+/// without depending on a particular version of an external crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn next_rgb(&mut self) -> [u8; 3] {
+        let [r, g, b, ..] = self.next_u64().to_le_bytes();
+        [r, g, b]
+    }
+}
+
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> [u8; 3] {
+    let h6 = h.fract() * 6.0;
+    let f = h6.fract();
+    let p = v * (1.0 - s);
+    let q = v * (1.0 - s * f);
+    let t = v * (1.0 - s * (1.0 - f));
+    let (r, g, b) = match h6 as u8 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    [r, g, b].map(|c| (c * 255.0).round() as u8)
+}
+
+fn srgb_to_lab(rgb: [u8; 3]) -> [f64; 3] {
+    let [r, g, b] = rgb.map(|c| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    // linear sRGB to XYZ, normalised by the D65 white point
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    let [fx, fy, fz] = [x, y, z].map(|t| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    });
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+fn lab_distance_sq(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter().zip(b.iter()).map(|(a, b)| (a - b).powi(2)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use validatrix::Validate;
+
+    use super::*;
+
+    #[test]
+    fn color_table() {
+        let colors = [
+            ImageLabelColor {
+                label_value: 1,
+                rgba: [255, 0, 0, 255],
+                extra: Default::default(),
+            },
+            ImageLabelColor {
+                label_value: 4,
+                rgba: [0, 255, 0, 255],
+                extra: Default::default(),
+            },
+        ];
+        let table: LabelColorTable = colors.iter().collect();
+        assert_eq!(table.get(4), Some([0, 255, 0, 255]));
+        assert_eq!(table.get(2), None);
+
+        let table = table.with_default([0, 0, 0, 0]);
+        assert_eq!(table.get(2), Some([0, 0, 0, 0]));
+        assert!(!table.contains(2));
+        assert_eq!(table.to_colors().len(), 2);
+    }
+
+    #[test]
+    fn palettes() {
+        for palette in [Palette::GoldenRatio, Palette::Glasbey] {
+            let colors = palette.generate((1..=50).chain([3]), 42);
+            assert_eq!(colors.len(), 50);
+            assert_eq!(colors.last().unwrap().label_value, 50);
+
+            let label = crate::v0_5::ImageLabel {
+                colors: Some(colors.clone()),
+                properties: None,
+                source: None,
+                extra: Default::default(),
+            };
+            label.validate().unwrap();
+
+            let again = palette.generate(1..=50, 42);
+            assert!(
+                colors
+                    .iter()
+                    .zip(again.iter())
+                    .all(|(a, b)| a.rgba == b.rgba)
+            );
+        }
+
+        let glasbey = Palette::Glasbey.generate(1..=50, 0);
+        let distinct: HashSet<_> = glasbey.iter().map(|c| c.rgba).collect();
+        assert_eq!(distinct.len(), 50);
+    }
+}
@@ -0,0 +1,599 @@
+//! Tabular access to label properties.
+
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+use validatrix::{Accumulator, Validate};
+
+use super::ImageLabelProperties;
+
+/// Name of the column holding each row's label value.
+pub const LABEL_VALUE_COLUMN: &str = "label-value";
+
+/// Type of the values in a [PropertyColumn], inferred from its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropertyType {
+    /// All values are booleans.
+    Bool,
+    /// All values are integers.
+    Integer,
+    /// All values are numbers, some of which are not integers.
+    Float,
+    /// All values are strings, or there are no values.
+    String,
+    /// Values are of mixed types, or are arrays or objects.
+    Json,
+}
+
+impl PropertyType {
+    /// Infer the narrowest type which describes all of the given values, ignoring nulls.
+    pub fn infer<'a>(values: impl IntoIterator<Item = &'a Value>) -> Self {
+        let mut out: Option<Self> = None;
+        for v in values {
+            let t = match v {
+                Value::Null => continue,
+                Value::Bool(_) => Self::Bool,
+                Value::Number(n) if n.is_f64() => Self::Float,
+                Value::Number(_) => Self::Integer,
+                Value::String(_) => Self::String,
+                Value::Array(_) | Value::Object(_) => return Self::Json,
+            };
+            out = Some(match (out, t) {
+                (None, t) => t,
+                (Some(prev), t) if prev == t => t,
+                (Some(Self::Integer | Self::Float), Self::Integer | Self::Float) => Self::Float,
+                _ => return Self::Json,
+            });
+        }
+        out.unwrap_or(Self::String)
+    }
+}
+
+/// A single property of every label, i.e. one column of a [LabelPropertiesTable].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyColumn {
+    /// Name of the property.
+    pub name: String,
+    /// Type of the property, inferred from its values.
+    pub property_type: PropertyType,
+    /// Value of the property for each row; `None` where a label does not have this property.
+    pub values: Vec<Option<Value>>,
+}
+
+/// Columnar view of [ImageLabelProperties], with one row per label value
+/// and one column per property key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelPropertiesTable {
+    /// Label value of each row.
+    pub label_values: Vec<u64>,
+    /// Properties, in order of first appearance.
+    pub columns: Vec<PropertyColumn>,
+}
+
+impl LabelPropertiesTable {
+    /// Build a table from rows of property maps keyed by label value,
+    /// e.g. from a deserialised JSON object.
+    pub fn from_rows(rows: impl IntoIterator<Item = (u64, Map<String, Value>)>) -> Self {
+        let properties: Vec<_> = rows
+            .into_iter()
+            .map(|(label_value, properties)| ImageLabelProperties {
+                label_value,
+                properties,
+            })
+            .collect();
+        properties.iter().collect()
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.label_values.len()
+    }
+
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.label_values.is_empty()
+    }
+
+    /// Get the column for the given property.
+    pub fn column(&self, name: &str) -> Option<&PropertyColumn> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Convert back into `properties` metadata; missing values are omitted.
+    pub fn to_properties(&self) -> Vec<ImageLabelProperties> {
+        self.label_values
+            .iter()
+            .enumerate()
+            .map(|(row, label_value)| ImageLabelProperties {
+                label_value: *label_value,
+                properties: self
+                    .columns
+                    .iter()
+                    .filter_map(|c| {
+                        c.values
+                            .get(row)
+                            .cloned()
+                            .flatten()
+                            .map(|v| (c.name.clone(), v))
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl<'a> FromIterator<&'a ImageLabelProperties> for LabelPropertiesTable {
+    fn from_iter<I: IntoIterator<Item = &'a ImageLabelProperties>>(iter: I) -> Self {
+        let rows: Vec<_> = iter.into_iter().collect();
+        let mut names: Vec<&String> = Vec::default();
+        let mut seen = HashSet::new();
+        for key in rows.iter().flat_map(|r| r.properties.keys()) {
+            if seen.insert(key) {
+                names.push(key);
+            }
+        }
+        let columns = names
+            .into_iter()
+            .map(|name| {
+                let values: Vec<_> = rows
+                    .iter()
+                    .map(|r| r.properties.get(name).cloned())
+                    .collect();
+                PropertyColumn {
+                    name: name.clone(),
+                    property_type: PropertyType::infer(values.iter().flatten()),
+                    values,
+                }
+            })
+            .collect();
+        Self {
+            label_values: rows.iter().map(|r| r.label_value).collect(),
+            columns,
+        }
+    }
+}
+
+impl Validate for LabelPropertiesTable {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        accum.with_key("label_values", |a| {
+            let mut set = HashSet::with_capacity(self.label_values.len());
+            for (idx, lbl) in self.label_values.iter().enumerate() {
+                if !set.insert(lbl) {
+                    a.add_failure_at(idx, format!("repeated label {lbl}"));
+                }
+            }
+        });
+        accum.with_key("columns", |a| {
+            let mut names = HashSet::with_capacity(self.columns.len());
+            for (idx, col) in self.columns.iter().enumerate() {
+                if col.name == LABEL_VALUE_COLUMN {
+                    a.add_failure_at(idx, format!("reserved column name {LABEL_VALUE_COLUMN}"));
+                }
+                if !names.insert(col.name.as_str()) {
+                    a.add_failure_at(idx, format!("repeated column name {}", col.name));
+                }
+                if col.values.len() != self.label_values.len() {
+                    a.add_failure_at(
+                        idx,
+                        format!(
+                            "got {} values for {} rows",
+                            col.values.len(),
+                            self.label_values.len()
+                        ),
+                    );
+                }
+            }
+        });
+    }
+}
+
+#[cfg(feature = "csv")]
+impl LabelPropertiesTable {
+    /// Read a table from delimited text with a header row.
+    ///
+    /// The header must include a [LABEL_VALUE_COLUMN] column, which must be an integer in every row.
+    /// Empty cells are treated as missing values.
+    ///
+    /// Columns with a typed header, as written by [LabelPropertiesTable::write_delimited]
+    /// (e.g. `class:string`), are parsed as that type, so that written tables load back unchanged.
+    /// Otherwise, each column's type is inferred from its text,
+    /// and array or object cells are parsed as JSON.
+    ///
+    /// A header is only treated as typed if the text after its last colon is a type name
+    /// (`bool`, `integer`, `float`, `string`, or `json`), so a header like `ratio:int` is an untyped
+    /// column of that name. An untyped column whose name does end in a type name,
+    /// such as `ratio:float`, cannot be told apart from a typed one and is read as typed.
+    /// Written headers always end in the type, so any name loads back unchanged.
+    ///
+    /// # Errors
+    /// Returns an error if the text is malformed,
+    /// if any label value is missing or not an integer,
+    /// if a cell in a typed column is not of that type,
+    /// or if the resulting table is not valid.
+    pub fn read_delimited<R: std::io::Read>(reader: R, delimiter: u8) -> crate::Result<Self> {
+        use crate::Error;
+
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .from_reader(reader);
+        let headers = rdr.headers()?.clone();
+        let Some(label_idx) = headers.iter().position(|h| h == LABEL_VALUE_COLUMN) else {
+            return Err(Error::general(format!(
+                "no {LABEL_VALUE_COLUMN} column in header"
+            )));
+        };
+
+        let mut label_values = Vec::default();
+        let mut cells: Vec<Vec<String>> = vec![Vec::default(); headers.len()];
+        for (row, record) in rdr.records().enumerate() {
+            let record = record?;
+            let label = record.get(label_idx).unwrap_or_default();
+            let label_value = label.trim().parse().map_err(|_| {
+                Error::general(format!(
+                    "row {row}: {LABEL_VALUE_COLUMN} {label:?} is missing or not an integer"
+                ))
+            })?;
+            label_values.push(label_value);
+            for (col, cell) in cells.iter_mut().zip(record.iter()) {
+                col.push(cell.to_string());
+            }
+        }
+
+        let columns = headers
+            .iter()
+            .zip(cells)
+            .enumerate()
+            .filter(|(idx, _)| *idx != label_idx)
+            .map(|(_, (header, col))| parse_column(header, &col))
+            .collect::<crate::Result<_>>()?;
+        let table = Self {
+            label_values,
+            columns,
+        };
+        table.validate()?;
+        Ok(table)
+    }
+
+    /// Read a table from comma-separated values; see [LabelPropertiesTable::read_delimited].
+    pub fn read_csv<R: std::io::Read>(reader: R) -> crate::Result<Self> {
+        Self::read_delimited(reader, b',')
+    }
+
+    /// Read a table from tab-separated values; see [LabelPropertiesTable::read_delimited].
+    pub fn read_tsv<R: std::io::Read>(reader: R) -> crate::Result<Self> {
+        Self::read_delimited(reader, b'\t')
+    }
+
+    /// Write the table as delimited text with a header row, starting with the [LABEL_VALUE_COLUMN] column.
+    ///
+    /// Each property's header is its name and type separated by a colon (e.g. `area:integer`).
+    /// Missing values are written as empty cells and nulls as `null`.
+    /// All values in [PropertyType::Json] columns are written as JSON,
+    /// as are strings which would otherwise be ambiguous (empty, `null`, or starting with `"`).
+    ///
+    /// # Errors
+    /// Returns an error if writing fails.
+    pub fn write_delimited<W: std::io::Write>(
+        &self,
+        writer: W,
+        delimiter: u8,
+    ) -> crate::Result<()> {
+        let mut wtr = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(writer);
+        wtr.write_record(
+            std::iter::once(LABEL_VALUE_COLUMN.to_string()).chain(
+                self.columns
+                    .iter()
+                    .map(|c| format!("{}{TYPE_SEPARATOR}{}", c.name, c.property_type.name())),
+            ),
+        )?;
+        for (row, label_value) in self.label_values.iter().enumerate() {
+            let mut record = vec![label_value.to_string()];
+            record.extend(self.columns.iter().map(|c| {
+                let value = c.values.get(row).and_then(Option::as_ref);
+                format_cell(c.property_type, value)
+            }));
+            wtr.write_record(&record)?;
+        }
+        wtr.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    /// Write the table as comma-separated values; see [LabelPropertiesTable::write_delimited].
+    pub fn write_csv<W: std::io::Write>(&self, writer: W) -> crate::Result<()> {
+        self.write_delimited(writer, b',')
+    }
+
+    /// Write the table as tab-separated values; see [LabelPropertiesTable::write_delimited].
+    pub fn write_tsv<W: std::io::Write>(&self, writer: W) -> crate::Result<()> {
+        self.write_delimited(writer, b'\t')
+    }
+}
+
+/// Separator between a property's name and its type in a typed header.
+#[cfg(feature = "csv")]
+const TYPE_SEPARATOR: char = ':';
+
+#[cfg(feature = "csv")]
+impl PropertyType {
+    /// Name of the type in a typed header.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::String => "string",
+            Self::Json => "json",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Bool,
+            Self::Integer,
+            Self::Float,
+            Self::String,
+            Self::Json,
+        ]
+        .into_iter()
+        .find(|t| t.name() == name)
+    }
+}
+
+/// Split a header into the property name and, if it is typed, its type.
+///
+/// Only a suffix naming a known [PropertyType] makes a header typed.
+#[cfg(feature = "csv")]
+fn split_header(header: &str) -> (&str, Option<PropertyType>) {
+    header
+        .rsplit_once(TYPE_SEPARATOR)
+        .and_then(|(name, t)| Some((name, Some(PropertyType::from_name(t)?))))
+        .unwrap_or((header, None))
+}
+
+#[cfg(feature = "csv")]
+fn format_cell(property_type: PropertyType, value: Option<&Value>) -> String {
+    match (property_type, value) {
+        (_, None) => String::default(),
+        (PropertyType::Json, Some(v)) => v.to_string(),
+        (_, Some(Value::String(s))) if s.is_empty() || s == "null" || s.starts_with('"') => {
+            Value::from(s.as_str()).to_string()
+        }
+        (_, Some(Value::String(s))) => s.clone(),
+        (_, Some(v)) => v.to_string(),
+    }
+}
+
+#[cfg(feature = "csv")]
+fn parse_integer(s: &str) -> Option<Value> {
+    s.parse::<i64>()
+        .map(Value::from)
+        .or_else(|_| s.parse::<u64>().map(Value::from))
+        .ok()
+}
+
+/// Integers are kept as integers, as floating point columns may contain both.
+#[cfg(feature = "csv")]
+fn parse_float(s: &str) -> Option<Value> {
+    parse_integer(s).or_else(|| {
+        s.parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+    })
+}
+
+#[cfg(feature = "csv")]
+type CellParser = fn(&str) -> Option<Value>;
+
+/// Parsers for inferring each column type, from narrowest to widest.
+#[cfg(feature = "csv")]
+const CELL_PARSERS: [(PropertyType, CellParser); 4] = [
+    (PropertyType::Bool, |s| {
+        s.parse::<bool>().ok().map(Value::from)
+    }),
+    (PropertyType::Integer, parse_integer),
+    (PropertyType::Float, parse_float),
+    (PropertyType::Json, |s| {
+        serde_json::from_str::<Value>(s)
+            .ok()
+            .filter(|v| matches!(v, Value::Array(_) | Value::Object(_) | Value::String(_)))
+    }),
+];
+
+/// Parse a non-empty cell of a column with a typed header; the inverse of [format_cell].
+#[cfg(feature = "csv")]
+fn parse_typed_cell(property_type: PropertyType, cell: &str) -> Option<Value> {
+    if cell == "null" {
+        return Some(Value::Null);
+    }
+    match property_type {
+        PropertyType::Bool => cell.parse::<bool>().ok().map(Value::from),
+        PropertyType::Integer => parse_integer(cell),
+        PropertyType::Float => parse_float(cell),
+        PropertyType::String if cell.starts_with('"') => {
+            serde_json::from_str::<String>(cell).ok().map(Value::from)
+        }
+        PropertyType::String => Some(Value::from(cell)),
+        PropertyType::Json => serde_json::from_str(cell).ok(),
+    }
+}
+
+#[cfg(feature = "csv")]
+fn parse_column(header: &str, cells: &[String]) -> crate::Result<PropertyColumn> {
+    let (name, property_type) = split_header(header);
+    if let Some(property_type) = property_type {
+        let values = cells
+            .iter()
+            .enumerate()
+            .map(|(row, c)| {
+                if c.is_empty() {
+                    return Ok(None);
+                }
+                parse_typed_cell(property_type, c).map(Some).ok_or_else(|| {
+                    crate::Error::general(format!(
+                        "row {row}: {name} {c:?} is not a valid {}",
+                        property_type.name()
+                    ))
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+        return Ok(PropertyColumn {
+            name: name.to_string(),
+            property_type,
+            values,
+        });
+    }
+
+    let any_present = cells.iter().any(|c| !c.is_empty());
+    let parsed = CELL_PARSERS
+        .iter()
+        .filter(|_| any_present)
+        .find_map(|(property_type, parse)| {
+            let values = cells
+                .iter()
+                .map(|c| {
+                    if c.is_empty() {
+                        Some(None)
+                    } else {
+                        parse(c).map(Some)
+                    }
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((*property_type, values))
+        });
+    let (property_type, values) = parsed.unwrap_or_else(|| {
+        let values = cells
+            .iter()
+            .map(|c| (!c.is_empty()).then(|| Value::from(c.as_str())))
+            .collect();
+        (PropertyType::String, values)
+    });
+    Ok(PropertyColumn {
+        name: name.to_string(),
+        property_type,
+        values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties() -> Vec<ImageLabelProperties> {
+        serde_json::from_str(
+            r#"[
+                {"label-value": 1, "area (pixels)": 1200, "class": "foo", "score": 0.5},
+                {"label-value": 4, "area (pixels)": 1650, "score": 1.5, "tags": ["a", "b"]}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn table_from_properties() {
+        let table: LabelPropertiesTable = properties().iter().collect();
+        assert_eq!(table.label_values, vec![1, 4]);
+        let names: Vec<_> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["area (pixels)", "class", "score", "tags"]);
+        let types: Vec<_> = table.columns.iter().map(|c| c.property_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                PropertyType::Integer,
+                PropertyType::String,
+                PropertyType::Float,
+                PropertyType::Json
+            ]
+        );
+        assert_eq!(table.column("class").unwrap().values[1], None);
+
+        let props = table.to_properties();
+        assert_eq!(props[1].properties.len(), 3);
+        assert!(!props[1].properties.contains_key("class"));
+    }
+
+    #[test]
+    fn repeated_label() {
+        let table = LabelPropertiesTable::from_rows([(1, Map::default()), (1, Map::default())]);
+        assert!(table.validate().is_err());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_roundtrip() {
+        let table: LabelPropertiesTable = properties().iter().collect();
+        let mut buf = Vec::default();
+        table.write_tsv(&mut buf).unwrap();
+        let text = String::from_utf8(buf.clone()).unwrap();
+        assert!(text.starts_with(
+            "label-value\tarea (pixels):integer\tclass:string\tscore:float\ttags:json\n"
+        ));
+
+        let table2 = LabelPropertiesTable::read_tsv(buf.as_slice()).unwrap();
+        assert_eq!(table2.label_values, table.label_values);
+        for (c1, c2) in table.columns.iter().zip(table2.columns.iter()) {
+            assert_eq!(c1.name, c2.name);
+            assert_eq!(c1.property_type, c2.property_type);
+        }
+        assert_eq!(
+            serde_json::to_value(table2.to_properties()).unwrap(),
+            serde_json::to_value(properties()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_missing_label() {
+        let csv = "label-value,class\n1,foo\n,bar\n";
+        assert!(LabelPropertiesTable::read_csv(csv.as_bytes()).is_err());
+        let csv = "label-value,class\n1,foo\n1,bar\n";
+        assert!(LabelPropertiesTable::read_csv(csv.as_bytes()).is_err());
+        let csv = "class\nfoo\n";
+        assert!(LabelPropertiesTable::read_csv(csv.as_bytes()).is_err());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_typed_roundtrip() {
+        let properties: Vec<ImageLabelProperties> = serde_json::from_value(serde_json::json!([
+            {"label-value": 1, "id": "007", "flag": "true", "note": "", "mixed": 1, "ratio": 2},
+            {"label-value": 2, "id": "null", "flag": "\"quoted\"", "mixed": "a", "ratio": 0.5},
+            {"label-value": 3, "id": null, "mixed": [1]}
+        ]))
+        .unwrap();
+        let table: LabelPropertiesTable = properties.iter().collect();
+        let mut buf = Vec::default();
+        table.write_csv(&mut buf).unwrap();
+
+        let table2 = LabelPropertiesTable::read_csv(buf.as_slice()).unwrap();
+        let types: Vec<_> = table2.columns.iter().map(|c| c.property_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                PropertyType::String,
+                PropertyType::String,
+                PropertyType::String,
+                PropertyType::Json,
+                PropertyType::Float
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(table2.to_properties()).unwrap(),
+            serde_json::to_value(properties).unwrap()
+        );
+
+        let csv = "label-value,ratio:int,time:10:30\n1,2,a\n";
+        let table = LabelPropertiesTable::read_csv(csv.as_bytes()).unwrap();
+        let names: Vec<_> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["ratio:int", "time:10:30"]);
+        let mut buf = Vec::default();
+        table.write_csv(&mut buf).unwrap();
+        let table2 = LabelPropertiesTable::read_csv(buf.as_slice()).unwrap();
+        assert_eq!(table2, table);
+
+        let csv = "label-value,id:integer\n1,seven\n";
+        assert!(LabelPropertiesTable::read_csv(csv.as_bytes()).is_err());
+    }
+}
@@ -7,12 +7,13 @@ use std::{collections::HashSet, path::PathBuf};
 use serde::{Deserialize, Serialize};
 use validatrix::{Accumulator, Validate};
 
+use super::{LabelColorTable, LabelPropertiesTable};
+
 /// `labels` metadata. A JSON array of paths to the labeled multiscale image(s).
 pub type Labels = Vec<String>;
 
 /// `image-label` metadata. Stores information about the display colors, source image, and optionally, further arbitrary properties of a label image.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ImageLabel {
     /// The version of the OME-NGFF "image-label" schema.
     pub version: super::ConstrainedVersion,
@@ -24,6 +25,9 @@ pub struct ImageLabel {
     /// Information about the original image from which the label image derives (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<ImageLabelSource>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for ImageLabel {
@@ -48,15 +52,29 @@ impl Validate for ImageLabel {
     }
 }
 
+impl ImageLabel {
+    /// Indexed lookup of this label image's `colors`.
+    pub fn color_table(&self) -> LabelColorTable {
+        self.colors.iter().flatten().collect()
+    }
+
+    /// Columnar view of this label image's `properties`.
+    pub fn properties_table(&self) -> LabelPropertiesTable {
+        self.properties.iter().flatten().collect()
+    }
+}
+
 /// [`ImageLabel`] `colors` element metadata. The colour of a unique image label.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ImageLabelColor {
     /// Integer label value.
     #[serde(rename = "label-value")]
     pub label_value: u64,
     /// Colour as RGBA array.
     pub rgba: [u8; 4],
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 pub(crate) fn validate_unique_labels<'a, T: HasLabelValue + 'a>(
@@ -88,7 +106,7 @@ impl HasLabelValue for ImageLabelProperties {
 }
 
 /// [`ImageLabel`] `properties` element metadata. Arbitrary metadata of a unique image label.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ImageLabelProperties {
     /// Integer label value.
     #[serde(rename = "label-value")]
@@ -99,10 +117,13 @@ pub struct ImageLabelProperties {
 }
 
 /// [`ImageLabel`] `source` metadata. Information about the source of a label image.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ImageLabelSource {
     /// Relative path to the zarr image group which this group labels.
     pub image: Option<PathBuf>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[cfg(test)]
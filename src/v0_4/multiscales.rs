@@ -5,15 +5,15 @@
 use std::collections::BTreeSet;
 
 use serde::{Deserialize, Serialize};
-use validatrix::{Accumulator, Validate};
+use validatrix::{Accumulator, Validate, ValidateContext};
 
 use crate::{MaybeNDim, NDim, ndim::validate_ndims, v0_4::AxisType};
 
-use super::{Axis, CoordinateTransform};
+use super::{Axis, CoordinateTransform, coordinate_transformations::compose_transforms};
 
 /// `multiscales` element metadata. Describes a multiscale image.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct MultiscaleImage {
     /// The version of the multiscale metadata of the image.
     pub version: super::ConstrainedVersion,
@@ -33,6 +33,9 @@ pub struct MultiscaleImage {
     /// A dictionary with additional information about the downscaling method (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<MultiscaleImageMetadata>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for MultiscaleImage {
@@ -116,6 +119,150 @@ pub(crate) fn valid_datasets(
     }
     validate_ndims(accum, expected_ndim, dss.iter());
     accum.validate_iter(dss);
+    valid_dataset_order(accum, dss);
+}
+
+/// Datasets must be ordered from highest to lowest resolution,
+/// i.e. no axis' scale may decrease from one level to the next.
+fn valid_dataset_order(accum: &mut Accumulator, dss: &[MultiscaleImageDataset]) {
+    let mut prev: Option<Vec<f64>> = None;
+    for (idx, ds) in dss.iter().enumerate() {
+        let Some(ndim) = ds.maybe_ndim() else {
+            prev = None;
+            continue;
+        };
+        // unresolvable transforms are reported elsewhere
+        let Ok((scale, _)) = compose_transforms(ndim, &ds.coordinate_transformations) else {
+            prev = None;
+            continue;
+        };
+        if let Some(p) = prev.as_ref().filter(|p| p.len() == scale.len()) {
+            for (ax_idx, (before, after)) in p.iter().zip(scale.iter()).enumerate() {
+                if after.abs() < before.abs() {
+                    accum.add_failure_at(
+                        idx,
+                        format!(
+                            "scale of axis {ax_idx} decreases from {before} to {after}; datasets must be ordered from highest to lowest resolution"
+                        ),
+                    );
+                }
+            }
+        }
+        prev = Some(scale);
+    }
+}
+
+/// Optional checks on the consistency of a multiscale image pyramid,
+/// which are stricter than the specification.
+///
+/// Used as the context for [ValidateContext] on multiscale images.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PyramidChecks {
+    /// Whether `channel` axes must have scale 1 and translation 0 at every resolution level.
+    pub channel_identity: bool,
+    /// Whether the downsampling factor between consecutive levels must be the same for every pair of levels, per axis.
+    pub consistent_downsampling: bool,
+    /// Relative tolerance for comparing floating-point values.
+    pub relative_tolerance: f64,
+}
+
+impl Default for PyramidChecks {
+    fn default() -> Self {
+        Self {
+            channel_identity: true,
+            consistent_downsampling: true,
+            relative_tolerance: 1e-6,
+        }
+    }
+}
+
+impl PyramidChecks {
+    fn approx_eq(&self, a: f64, b: f64) -> bool {
+        (a - b).abs() <= self.relative_tolerance * a.abs().max(b.abs())
+    }
+}
+
+pub(crate) fn valid_pyramid(
+    accum: &mut Accumulator,
+    checks: &PyramidChecks,
+    axes: &[Axis],
+    global: Option<&[CoordinateTransform]>,
+    dss: &[MultiscaleImageDataset],
+) {
+    let ndim = axes.len();
+    let channels: Vec<_> = axes
+        .iter()
+        .enumerate()
+        .filter(|(_, ax)| ax.r#type == Some(AxisType::Channel))
+        .collect();
+
+    let valid_channels = |a: &mut Accumulator, cts: &[CoordinateTransform]| {
+        let Ok((scale, translation)) = compose_transforms(ndim, cts) else {
+            return;
+        };
+        for (idx, ax) in channels.iter() {
+            if scale[*idx] != 1.0 {
+                a.add_failure(format!(
+                    "channel axis '{}' has scale {}, expected 1",
+                    ax.name, scale[*idx]
+                ));
+            }
+            if translation[*idx] != 0.0 {
+                a.add_failure(format!(
+                    "channel axis '{}' has translation {}, expected 0",
+                    ax.name, translation[*idx]
+                ));
+            }
+        }
+    };
+
+    if checks.channel_identity {
+        if let Some(g) = global {
+            accum.with_key("coordinateTransformations", |a| valid_channels(a, g));
+        }
+        accum.with_key("datasets", |a| {
+            for (idx, ds) in dss.iter().enumerate() {
+                a.with_keys(&[idx.into(), "coordinateTransformations".into()], |a2| {
+                    valid_channels(a2, &ds.coordinate_transformations)
+                });
+            }
+        });
+    }
+
+    if checks.consistent_downsampling {
+        let Ok(scales) = dss
+            .iter()
+            .map(|ds| compose_transforms(ndim, &ds.coordinate_transformations).map(|(s, _)| s))
+            .collect::<crate::Result<Vec<_>>>()
+        else {
+            return;
+        };
+        accum.with_key("datasets", |a| {
+            let mut first_factors: Option<Vec<f64>> = None;
+            for (idx, pair) in scales.windows(2).enumerate() {
+                let factors: Vec<f64> = pair[0]
+                    .iter()
+                    .zip(pair[1].iter())
+                    .map(|(before, after)| after / before)
+                    .collect();
+                let Some(first) = first_factors.as_ref() else {
+                    first_factors = Some(factors);
+                    continue;
+                };
+                for ((ax, expected), actual) in axes.iter().zip(first).zip(factors.iter()) {
+                    if !checks.approx_eq(*expected, *actual) {
+                        a.add_failure_at(
+                            idx + 1,
+                            format!(
+                                "downsampling factor {actual} on axis '{}' is inconsistent with previous factor {expected}",
+                                ax.name
+                            ),
+                        );
+                    }
+                }
+            }
+        });
+    }
 }
 
 pub(crate) fn valid_transforms(
@@ -123,14 +270,15 @@ pub(crate) fn valid_transforms(
     expected_ndim: Option<usize>,
     cts: &[CoordinateTransform],
 ) {
-    // todo: validate that channel axes' scales=1 and translation=0
+    // channel axes' scale and translation are checked by `PyramidChecks`,
+    // as the specification's own examples use non-zero channel translations
     validate_ndims(accum, expected_ndim, cts.iter());
     accum.validate_iter(cts);
     let mut has_scale = false;
     let mut has_translation = false;
     for (idx, ct) in cts.iter().enumerate() {
         match ct {
-            CoordinateTransform::Identity => {
+            CoordinateTransform::Identity { .. } => {
                 accum.add_failure_at(idx, "identity transform cannot be used here");
             }
             CoordinateTransform::Translation(_t) => {
@@ -158,6 +306,21 @@ pub(crate) fn valid_transforms(
     }
 }
 
+impl ValidateContext for MultiscaleImage {
+    type Context = PyramidChecks;
+
+    fn validate_inner(&self, context: &Self::Context, accum: &mut Accumulator) {
+        Validate::validate_inner(self, accum);
+        valid_pyramid(
+            accum,
+            context,
+            &self.axes,
+            self.coordinate_transformations.as_deref(),
+            &self.datasets,
+        );
+    }
+}
+
 impl NDim for &MultiscaleImage {
     fn ndim(&self) -> usize {
         self.axes.len()
@@ -165,13 +328,16 @@ impl NDim for &MultiscaleImage {
 }
 
 /// [`MultiscaleImage`] `datasets` element metadata. Describes an individual resolution level.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct MultiscaleImageDataset {
     /// The path to the array for this resolution relative to the current zarr group.
     pub path: String,
     /// A list of transformations that map the data coordinates to the physical coordinates (as specified by "axes") for this resolution level.
     pub coordinate_transformations: Vec<CoordinateTransform>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for MultiscaleImageDataset {
@@ -191,12 +357,233 @@ impl MaybeNDim for MultiscaleImageDataset {
     }
 }
 
+impl MultiscaleImage {
+    /// The [`type`](MultiscaleImage::type) of downscaling method, if given.
+    pub fn downsampling_type(&self) -> Option<DownsamplingType> {
+        self.r#type.as_deref().map(DownsamplingType::from)
+    }
+
+    /// Set the [`type`](MultiscaleImage::type) of downscaling method.
+    pub fn set_downsampling_type(&mut self, downsampling_type: DownsamplingType) {
+        self.r#type = Some(downsampling_type.to_string());
+    }
+}
+
 /// [`MultiscaleImage`] `metadata` metadata. Information about the downscaling method.
 ///
-/// E.g. fields: `description`, `method`, `version`, `args`, `kwargs`.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// E.g. fields: `description`, `method`, `version`, `args`, `kwargs`,
+/// which have typed accessors.
+/// Any other fields are preserved.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct MultiscaleImageMetadata(pub serde_json::Map<String, serde_json::Value>);
 
+impl MultiscaleImageMetadata {
+    fn get_str(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.as_str())
+    }
+
+    /// Description of the downscaling method, if given as a string.
+    pub fn description(&self) -> Option<&str> {
+        self.get_str("description")
+    }
+
+    /// Set the description of the downscaling method.
+    pub fn set_description(&mut self, description: impl Into<String>) {
+        self.0
+            .insert("description".into(), description.into().into());
+    }
+
+    /// Fully-qualified name of the function used for downscaling, if given as a string.
+    pub fn method(&self) -> Option<DownsamplingMethod> {
+        self.get_str("method").map(DownsamplingMethod::from)
+    }
+
+    /// Set the fully-qualified name of the function used for downscaling.
+    pub fn set_method(&mut self, method: DownsamplingMethod) {
+        self.0.insert("method".into(), method.to_string().into());
+    }
+
+    /// Version of the package providing the downscaling method, if given as a string.
+    pub fn version(&self) -> Option<&str> {
+        self.get_str("version")
+    }
+
+    /// Set the version of the package providing the downscaling method.
+    pub fn set_version(&mut self, version: impl Into<String>) {
+        self.0.insert("version".into(), version.into().into());
+    }
+
+    /// Positional arguments passed to the downscaling method.
+    ///
+    /// The specification does not constrain their representation.
+    pub fn args(&self) -> Option<&serde_json::Value> {
+        self.0.get("args")
+    }
+
+    /// Set the positional arguments passed to the downscaling method.
+    pub fn set_args(&mut self, args: serde_json::Value) {
+        self.0.insert("args".into(), args);
+    }
+
+    /// Keyword arguments passed to the downscaling method, if given as an object.
+    pub fn kwargs(&self) -> Option<&serde_json::Map<String, serde_json::Value>> {
+        self.0.get("kwargs").and_then(|v| v.as_object())
+    }
+
+    /// Set the keyword arguments passed to the downscaling method.
+    pub fn set_kwargs(&mut self, kwargs: serde_json::Map<String, serde_json::Value>) {
+        self.0.insert("kwargs".into(), kwargs.into());
+    }
+}
+
+/// Name of a downscaling type or method which has no named variant.
+///
+/// This can only be created by parsing a name with `From<&str>` or [FromStr](std::str::FromStr),
+/// so that known names always map to their own variant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomDownsampling(String);
+
+impl CustomDownsampling {
+    /// The name as written in the metadata.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for CustomDownsampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// [`MultiscaleImage`] `type` metadata. The type of downscaling method.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DownsamplingType {
+    /// Gaussian smoothing followed by subsampling.
+    Gaussian,
+    /// Laplacian pyramid.
+    Laplacian,
+    /// Smoothing and subsampling with an arbitrary factor.
+    Reduce,
+    /// Mean of local blocks.
+    LocalMean,
+    /// Nearest-neighbour subsampling.
+    Nearest,
+    /// Spline interpolation.
+    Zoom,
+    /// Any other downscaling method.
+    Custom(CustomDownsampling),
+}
+
+impl DownsamplingType {
+    fn as_str(&self) -> &str {
+        match self {
+            DownsamplingType::Gaussian => "gaussian",
+            DownsamplingType::Laplacian => "laplacian",
+            DownsamplingType::Reduce => "reduce",
+            DownsamplingType::LocalMean => "local_mean",
+            DownsamplingType::Nearest => "nearest",
+            DownsamplingType::Zoom => "zoom",
+            DownsamplingType::Custom(s) => s.as_str(),
+        }
+    }
+}
+
+impl From<&str> for DownsamplingType {
+    fn from(value: &str) -> Self {
+        match value {
+            "gaussian" => DownsamplingType::Gaussian,
+            "laplacian" => DownsamplingType::Laplacian,
+            "reduce" => DownsamplingType::Reduce,
+            "local_mean" => DownsamplingType::LocalMean,
+            "nearest" => DownsamplingType::Nearest,
+            "zoom" => DownsamplingType::Zoom,
+            s => DownsamplingType::Custom(CustomDownsampling(s.to_string())),
+        }
+    }
+}
+
+impl std::str::FromStr for DownsamplingType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl std::fmt::Display for DownsamplingType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// [`MultiscaleImageMetadata`] `method` metadata. The function used for downscaling.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DownsamplingMethod {
+    /// `skimage.transform.pyramid_gaussian`
+    PyramidGaussian,
+    /// `skimage.transform.pyramid_laplacian`
+    PyramidLaplacian,
+    /// `skimage.transform.pyramid_reduce`
+    PyramidReduce,
+    /// `skimage.transform.downscale_local_mean`
+    DownscaleLocalMean,
+    /// `skimage.transform.rescale`
+    Rescale,
+    /// `skimage.transform.resize`
+    Resize,
+    /// `scipy.ndimage.zoom`
+    Zoom,
+    /// Any other function.
+    Custom(CustomDownsampling),
+}
+
+impl DownsamplingMethod {
+    fn as_str(&self) -> &str {
+        match self {
+            DownsamplingMethod::PyramidGaussian => "skimage.transform.pyramid_gaussian",
+            DownsamplingMethod::PyramidLaplacian => "skimage.transform.pyramid_laplacian",
+            DownsamplingMethod::PyramidReduce => "skimage.transform.pyramid_reduce",
+            DownsamplingMethod::DownscaleLocalMean => "skimage.transform.downscale_local_mean",
+            DownsamplingMethod::Rescale => "skimage.transform.rescale",
+            DownsamplingMethod::Resize => "skimage.transform.resize",
+            DownsamplingMethod::Zoom => "scipy.ndimage.zoom",
+            DownsamplingMethod::Custom(s) => s.as_str(),
+        }
+    }
+}
+
+impl From<&str> for DownsamplingMethod {
+    fn from(value: &str) -> Self {
+        match value {
+            "skimage.transform.pyramid_gaussian" => DownsamplingMethod::PyramidGaussian,
+            "skimage.transform.pyramid_laplacian" => DownsamplingMethod::PyramidLaplacian,
+            "skimage.transform.pyramid_reduce" => DownsamplingMethod::PyramidReduce,
+            "skimage.transform.downscale_local_mean" => DownsamplingMethod::DownscaleLocalMean,
+            "skimage.transform.rescale" => DownsamplingMethod::Rescale,
+            "skimage.transform.resize" => DownsamplingMethod::Resize,
+            "scipy.ndimage.zoom" => DownsamplingMethod::Zoom,
+            s => DownsamplingMethod::Custom(CustomDownsampling(s.to_string())),
+        }
+    }
+}
+
+impl std::str::FromStr for DownsamplingMethod {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl std::fmt::Display for DownsamplingMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::v0_4::OmeNgffGroupAttributes;
@@ -225,4 +612,67 @@ mod tests {
         let ome_metadata: OmeNgffGroupAttributes = serde_json::from_str(json).unwrap();
         let _multiscales: Vec<MultiscaleImage> = ome_metadata.multiscales.unwrap();
     }
+
+    #[test]
+    fn downsampling_metadata() {
+        let mut meta: MultiscaleImageMetadata = serde_json::from_str(
+            r#"{
+                "description": "the fields in metadata depend on the downscaling implementation",
+                "method": "skimage.transform.pyramid_gaussian",
+                "version": "0.16.1",
+                "args": "[true]",
+                "kwargs": {"multichannel": true},
+                "extra": 1
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(meta.method(), Some(DownsamplingMethod::PyramidGaussian));
+        assert_eq!(meta.version(), Some("0.16.1"));
+        assert_eq!(meta.args(), Some(&serde_json::json!("[true]")));
+        assert_eq!(meta.kwargs().unwrap()["multichannel"], true);
+
+        meta.set_method("my.downsampler".into());
+        let value = serde_json::to_value(&meta).unwrap();
+        assert_eq!(value["method"], "my.downsampler");
+        assert_eq!(value["extra"], 1);
+        let meta: MultiscaleImageMetadata = serde_json::from_value(value).unwrap();
+        assert!(matches!(
+            meta.method(),
+            Some(DownsamplingMethod::Custom(c)) if c.as_str() == "my.downsampler"
+        ));
+    }
+
+    #[test]
+    fn downsampling_type() {
+        assert_eq!(
+            DownsamplingType::from("local_mean"),
+            DownsamplingType::LocalMean
+        );
+        assert_eq!(DownsamplingType::from("bicubic").to_string(), "bicubic");
+    }
+
+    #[test]
+    fn known_names_are_not_custom() {
+        assert_eq!(
+            "gaussian".parse::<DownsamplingType>(),
+            Ok(DownsamplingType::Gaussian)
+        );
+        assert_eq!(
+            "scipy.ndimage.zoom".parse::<DownsamplingMethod>(),
+            Ok(DownsamplingMethod::Zoom)
+        );
+        for name in [
+            "gaussian",
+            "laplacian",
+            "reduce",
+            "local_mean",
+            "nearest",
+            "zoom",
+        ] {
+            assert!(!matches!(
+                DownsamplingType::from(name),
+                DownsamplingType::Custom(_)
+            ));
+        }
+    }
 }
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use validatrix::Validate;
 
 /// Transitional information specific to the channels of an image and how to render it.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Omero {
     /// Description of channels of the image.
     pub channels: Vec<Channel>,
@@ -20,7 +20,7 @@ impl Validate for Omero {
 }
 
 /// Describes the channels of an image in OMERGO format.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Channel {
     /// Channel color, stored as a hex RGB string.
     pub color: Color,
@@ -94,7 +94,7 @@ impl<'de> Deserialize<'de> for Color {
 }
 
 /// Describes the windowing of a channel in OMERO format.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Window {
     /// Minimum value of the window.
     pub min: f64,
@@ -104,6 +104,9 @@ pub struct Window {
     pub start: f64,
     /// End value of the window.
     pub end: f64,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for Window {
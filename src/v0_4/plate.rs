@@ -2,14 +2,19 @@
 //!
 //! <https://ngff.openmicroscopy.org/0.4/#plate-md>.
 
-use std::{collections::HashSet, num::NonZeroU64, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    num::NonZeroU64,
+    path::PathBuf,
+};
 
 use serde::{Deserialize, Serialize};
-use validatrix::{Accumulator, Validate};
+use validatrix::{Accumulator, Validate, ValidateContext};
+
+use super::{Well, WellImage};
 
 /// `plate` metadata. For high-content screening datasets.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Plate {
     /// The version of the multiscale metadata of the image.
     pub version: super::ConstrainedVersion,
@@ -28,6 +33,9 @@ pub struct Plate {
     pub rows: Vec<PlateRow>,
     /// Defines the wells of the plate.
     pub wells: Vec<PlateWell>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for Plate {
@@ -128,9 +136,106 @@ impl Validate for Plate {
     }
 }
 
+/// Validate the plate along with the `well` metadata of its wells, keyed by well path (e.g. `"A/1"`).
+///
+/// As well as the plate's own validation, this checks that
+/// - every well listed in the plate has metadata, and vice versa
+/// - every image's `acquisition` refers to one of the plate's `acquisitions` if it lists any,
+///   and is given if there are multiple acquisitions
+/// - no well has more images than the plate's `field_count`
+/// - no well has more images for an acquisition than its `maximum_field_count`
+impl ValidateContext for Plate {
+    type Context = BTreeMap<String, Well>;
+
+    fn validate_inner(&self, context: &Self::Context, accum: &mut Accumulator) {
+        Validate::validate_inner(self, accum);
+        validate_plate_wells(
+            accum,
+            self.acquisitions.as_deref(),
+            self.field_count,
+            &self.wells,
+            context
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.images.as_slice())),
+        );
+    }
+}
+
+/// Check a plate's wells against the images in each well's metadata.
+pub(crate) fn validate_plate_wells<'a>(
+    accum: &mut Accumulator,
+    acquisitions: Option<&[PlateAcquisition]>,
+    field_count: Option<NonZeroU64>,
+    wells: &[PlateWell],
+    well_images: impl IntoIterator<Item = (&'a str, &'a [WellImage])>,
+) {
+    let mut well_images: BTreeMap<&str, &[WellImage]> = well_images.into_iter().collect();
+    // `acquisition` is only constrained if the plate lists acquisitions
+    let check_acquisitions = acquisitions.is_some();
+    let acquisitions = acquisitions.unwrap_or_default();
+    let max_per_acquisition: HashMap<u64, Option<NonZeroU64>> = acquisitions
+        .iter()
+        .map(|a| (a.id, a.maximum_field_count))
+        .collect();
+
+    accum.with_key("wells", |acc| {
+        for (idx, well) in wells.iter().enumerate() {
+            acc.with_key(idx, |w_acc| {
+                let path = well.path.to_string_lossy();
+                let Some(images) = well_images.remove(path.as_ref()) else {
+                    w_acc.add_failure_at("path", format!("no well metadata for {path}"));
+                    return;
+                };
+
+                if let Some(fc) = field_count {
+                    if images.len() as u64 > fc.get() {
+                        w_acc.add_failure(format!(
+                            "well {path} has {} images, more than the plate's field count {fc}",
+                            images.len()
+                        ));
+                    }
+                }
+
+                let mut per_acquisition: BTreeMap<u64, u64> = BTreeMap::default();
+                for (img_idx, img) in images.iter().enumerate() {
+                    match img.acquisition {
+                        Some(id) if check_acquisitions && !max_per_acquisition.contains_key(&id) => {
+                            w_acc.add_failure(format!(
+                                "well {path} image {img_idx} refers to unknown acquisition {id}"
+                            ));
+                        }
+                        Some(id) => *per_acquisition.entry(id).or_default() += 1,
+                        None if acquisitions.len() > 1 => {
+                            w_acc.add_failure(format!(
+                                "well {path} image {img_idx} has no acquisition, but the plate has multiple"
+                            ));
+                        }
+                        None => (),
+                    }
+                }
+
+                for (id, count) in per_acquisition {
+                    if let Some(Some(max)) = max_per_acquisition.get(&id) {
+                        if count > max.get() {
+                            w_acc.add_failure(format!(
+                                "well {path} has {count} images for acquisition {id}, more than its maximum field count {max}"
+                            ));
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    for path in well_images.keys() {
+        accum.add_failure(format!(
+            "well metadata for {path} is not listed in the plate"
+        ));
+    }
+}
+
 /// [`Plate`] `acquisitions` element metadata. Defines a plate acquisition.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PlateAcquisition {
     /// A unique integer identifier that fields of view can refer to.
     pub id: u64,
@@ -149,6 +254,9 @@ pub struct PlateAcquisition {
     /// An epoch timestamp specifying the end timestamp of the acquisition (optional).
     #[serde(skip_serializing_if = "Option::is_none", rename = "endtime")]
     pub end_time: Option<u64>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for PlateAcquisition {
@@ -162,11 +270,13 @@ impl Validate for PlateAcquisition {
 }
 
 /// [`Plate`] `columns` element metadata. Defines a plate column.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PlateColumn {
     /// Specifies the unique column mame.
     pub name: String,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for PlateColumn {
@@ -178,11 +288,13 @@ impl Validate for PlateColumn {
 }
 
 /// [`Plate`] `rows` element metadata. Defines a plate row.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PlateRow {
     /// Specifies the unique row mame.
     pub name: String,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for PlateRow {
@@ -193,7 +305,7 @@ impl Validate for PlateRow {
     }
 }
 
-fn validate_alphanum(accum: &mut Accumulator, s: &str) {
+pub(crate) fn validate_alphanum(accum: &mut Accumulator, s: &str) {
     for c in s.chars() {
         if !c.is_alphanumeric() {
             accum.add_failure(format!("not alphanumeric: {s}"));
@@ -203,8 +315,7 @@ fn validate_alphanum(accum: &mut Accumulator, s: &str) {
 }
 
 /// [`Plate`] `wells` element metadata. Defines a plate well.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PlateWell {
     /// A string specifying the path to the well subgroup.
     pub path: PathBuf,
@@ -214,6 +325,9 @@ pub struct PlateWell {
     /// Specifies the column index.
     #[serde(rename = "columnIndex")]
     pub column_index: usize,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[cfg(test)]
@@ -241,4 +355,61 @@ mod tests {
         let ome_metadata: OmeNgffGroupAttributes = serde_json::from_str(json).unwrap();
         let _plate: Plate = ome_metadata.plate.unwrap();
     }
+
+    #[test]
+    fn plate_with_wells() {
+        let plate: Plate = serde_json::from_value(serde_json::json!({
+            "version": "0.4",
+            "acquisitions": [{"id": 1, "maximumfieldcount": 1}, {"id": 2}],
+            "field_count": 2,
+            "columns": [{"name": "1"}],
+            "rows": [{"name": "A"}, {"name": "B"}],
+            "wells": [
+                {"path": "A/1", "rowIndex": 0, "columnIndex": 0},
+                {"path": "B/1", "rowIndex": 1, "columnIndex": 0}
+            ]
+        }))
+        .unwrap();
+        let well = |acqs: &[Option<u64>]| -> Well {
+            let images: Vec<_> = acqs
+                .iter()
+                .enumerate()
+                .map(|(idx, acq)| serde_json::json!({"path": idx.to_string(), "acquisition": acq}))
+                .collect();
+            serde_json::from_value(serde_json::json!({"version": "0.4", "images": images})).unwrap()
+        };
+
+        let wells = BTreeMap::from([
+            ("A/1".to_string(), well(&[Some(1), Some(2)])),
+            ("B/1".to_string(), well(&[Some(2)])),
+        ]);
+        ValidateContext::validate(&plate, &wells).unwrap();
+
+        let wells = BTreeMap::from([
+            // too many fields, too many for acquisition 1, unknown acquisition, missing acquisition
+            ("A/1".to_string(), well(&[Some(1), Some(1), Some(3), None])),
+            ("C/1".to_string(), well(&[Some(2)])),
+        ]);
+        let err = ValidateContext::validate(&plate, &wells).unwrap_err();
+        // the above, plus B/1 missing and C/1 unlisted
+        assert_eq!(err.len(), 6);
+    }
+
+    #[test]
+    fn plate_without_acquisitions() {
+        let plate: Plate = serde_json::from_value(serde_json::json!({
+            "version": "0.4",
+            "columns": [{"name": "1"}],
+            "rows": [{"name": "A"}],
+            "wells": [{"path": "A/1", "rowIndex": 0, "columnIndex": 0}]
+        }))
+        .unwrap();
+        let well: Well = serde_json::from_value(serde_json::json!({
+            "version": "0.4",
+            "images": [{"path": "0", "acquisition": 1}, {"path": "1", "acquisition": 2}]
+        }))
+        .unwrap();
+        let wells = BTreeMap::from([("A/1".to_string(), well)]);
+        ValidateContext::validate(&plate, &wells).unwrap();
+    }
 }
@@ -2,23 +2,50 @@
 //!
 //! <https://ngff.openmicroscopy.org/0.4/#well-md>.
 
-use std::path::PathBuf;
+use std::{collections::HashSet, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
+use validatrix::{Accumulator, Validate};
+
+use super::plate::validate_alphanum;
 
 /// `well` metadata. Describes all fields of views under a given well.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Well {
     /// The version of the "well" schema.
     pub version: super::ConstrainedVersion,
     /// Specifies the fields of views of the well.
     pub images: Vec<WellImage>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Validate for Well {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        accum.with_key("images", |a| validate_well_images(a, &self.images));
+    }
+}
+
+/// Check that the paths of a well's images are alphanumeric and unique.
+pub(crate) fn validate_well_images(accum: &mut Accumulator, images: &[WellImage]) {
+    let mut paths = HashSet::with_capacity(images.len());
+    for (idx, img) in images.iter().enumerate() {
+        accum.with_keys(&[idx.into(), "path".into()], |a| {
+            let Some(path) = img.path.to_str() else {
+                a.add_failure("not valid unicode");
+                return;
+            };
+            validate_alphanum(a, path);
+            if !paths.insert(path) {
+                a.add_failure(format!("not unique: {path}"));
+            }
+        });
+    }
 }
 
 /// [`Well`] `images` element metadata. Specifies a field of view for a given well.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct WellImage {
     /// A string specifying the path to the field of view.
     ///
@@ -29,6 +56,9 @@ pub struct WellImage {
     /// If multiple acquisitions were performed in the plate, it MUST contain an acquisition key whose value MUST be an integer identifying the acquisition which MUST match one of the acquisition JSON objects defined in the plate metadata
     #[serde(skip_serializing_if = "Option::is_none")]
     pub acquisition: Option<u64>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[cfg(test)]
@@ -56,4 +86,14 @@ mod tests {
         let ome_metadata: OmeNgffGroupAttributes = serde_json::from_str(json).unwrap();
         let _well: Well = ome_metadata.well.unwrap();
     }
+
+    #[test]
+    fn invalid_image_paths() {
+        let well: Well = serde_json::from_value(serde_json::json!({
+            "version": "0.4",
+            "images": [{"path": "0"}, {"path": "0"}, {"path": "a/b"}]
+        }))
+        .unwrap();
+        assert_eq!(well.validate().unwrap_err().len(), 2);
+    }
 }
@@ -1,20 +1,37 @@
+pub(crate) mod diff;
+pub(crate) mod extent;
 pub(crate) mod labels;
 pub(crate) mod multiscales;
+#[cfg(feature = "json-patch")]
+pub(crate) mod patch;
 pub(crate) mod plate;
+pub(crate) mod plate_grid;
+pub(crate) mod plate_map;
+pub(crate) mod selection;
 pub(crate) mod well;
 
 use crate::v0_4;
 pub use crate::v0_4::axes::*;
 pub use crate::v0_4::bioformats2raw_layout::*;
 pub use crate::v0_4::coordinate_transformations::*;
-pub use crate::v0_4::multiscales::{MultiscaleImageDataset, MultiscaleImageMetadata};
+pub use crate::v0_4::multiscales::{
+    CustomDownsampling, DownsamplingMethod, DownsamplingType, MultiscaleImageDataset,
+    MultiscaleImageMetadata, PyramidChecks,
+};
 pub use crate::v0_4::omero::*;
 pub use crate::v0_4::plate::{PlateAcquisition, PlateColumn, PlateRow, PlateWell};
 pub use crate::v0_4::well::WellImage;
 
+pub use diff::*;
+pub use extent::*;
 pub use labels::*;
 pub use multiscales::*;
+#[cfg(feature = "json-patch")]
+pub use patch::*;
 pub use plate::*;
+pub use plate_grid::*;
+pub use plate_map::*;
+pub use selection::*;
 use serde::Deserialize;
 use serde::Serialize;
 use validatrix::{Accumulator, Validate};
@@ -25,7 +42,7 @@ use serde::de::Error;
 crate::constrained_version!(ConstrainedVersion, "==0.5", "0.5");
 
 /// OME-Zarr "ome" fields.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct OmeFields {
     /// OME-Zarr version.
     pub version: ConstrainedVersion,
@@ -50,10 +67,18 @@ pub struct OmeFields {
     /// Transitional OMERO metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub omero: Option<Omero>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for OmeFields {
     fn validate_inner(&self, accum: &mut Accumulator) {
+        // fields are flattened into this object
+        if let Some(b) = self.bioformats2raw.as_ref() {
+            b.validate_inner(accum);
+        }
+
         if let Some(m) = self.multiscales.as_ref() {
             accum.with_key("multiscales", |a| {
                 if m.is_empty() {
@@ -71,24 +96,48 @@ impl Validate for OmeFields {
             accum.validate_member_at("plate", p);
         }
 
+        if let Some(w) = self.well.as_ref() {
+            accum.validate_member_at("well", w);
+        }
+
         if let Some(o) = self.omero.as_ref() {
             accum.validate_member_at("omero", o);
         }
     }
 }
 
+/// Group attributes alongside the `ome` key, kept as raw JSON.
+///
+/// This is the default extension type of [OmeZarrGroupAttributes];
+/// it accepts any fields and does not validate them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RawExtensions(pub serde_json::Map<String, serde_json::Value>);
+
+impl Validate for RawExtensions {
+    fn validate_inner(&self, _accum: &mut Accumulator) {}
+}
+
 /// OME-Zarr top-level group attributes.
 ///
 /// This can be deserialised from a representation of a group's user attributes.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct OmeZarrGroupAttributes {
+///
+/// Attributes alongside the `ome` key, such as vendor namespaces,
+/// are deserialised into the extension type `E` and validated with it.
+/// By default, they are kept as [RawExtensions].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OmeZarrGroupAttributes<E = RawExtensions> {
     /// OME-Zarr "ome" fields.
     pub ome: OmeFields,
+    /// Attributes other than `ome`.
+    #[serde(flatten)]
+    pub extensions: E,
 }
 
-impl Validate for OmeZarrGroupAttributes {
+impl<E: Validate> Validate for OmeZarrGroupAttributes<E> {
     fn validate_inner(&self, accum: &mut Accumulator) {
         accum.validate_member_at("ome", &self.ome);
+        // fields are flattened into this object
+        self.extensions.validate_inner(accum);
     }
 }
 
@@ -105,13 +154,42 @@ impl From<v0_4::OmeNgffGroupAttributes> for OmeFields {
             plate: value.plate.map(Into::into),
             well: value.well.map(Into::into),
             omero: value.omero,
+            extra: value.extra,
         }
     }
 }
 
 impl From<v0_4::OmeNgffGroupAttributes> for OmeZarrGroupAttributes {
-    fn from(value: v0_4::OmeNgffGroupAttributes) -> Self {
-        Self { ome: value.into() }
+    /// Unknown fields were stored freely in the group attributes,
+    /// so they are kept there rather than moved into the `ome` namespace.
+    fn from(mut value: v0_4::OmeNgffGroupAttributes) -> Self {
+        let extra = std::mem::take(&mut value.extra);
+        Self {
+            ome: value.into(),
+            extensions: RawExtensions(extra),
+        }
+    }
+}
+
+/// Zarr format version of OME-Zarr 0.5 groups.
+pub const ZARR_FORMAT: u64 = 3;
+
+/// Zarr `node_type` of groups.
+pub const NODE_TYPE_GROUP: &str = "group";
+
+/// Check the Zarr core metadata of a group's `zarr.json`.
+pub(crate) fn validate_group_node(accum: &mut Accumulator, zarr_format: u64, node_type: &str) {
+    if zarr_format != ZARR_FORMAT {
+        accum.add_failure_at(
+            "zarr_format",
+            format!("got Zarr format {zarr_format}, expected {ZARR_FORMAT}"),
+        );
+    }
+    if node_type != NODE_TYPE_GROUP {
+        accum.add_failure_at(
+            "node_type",
+            format!("got node type {node_type:?}, expected {NODE_TYPE_GROUP:?}"),
+        );
     }
 }
 
@@ -119,25 +197,65 @@ impl From<v0_4::OmeNgffGroupAttributes> for OmeZarrGroupAttributes {
 ///
 /// This can be deserialised from a representation of the whole metadata document
 /// (i.e. the contents of `zarr.json` in zarr v3, which includes user attributes and core metadata).
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct OmeZarrGroupMetadata {
+///
+/// Attributes other than `ome` are deserialised into the extension type `E`
+/// (see [OmeZarrGroupAttributes]).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OmeZarrGroupMetadata<E = RawExtensions> {
+    /// Zarr format version; must be [ZARR_FORMAT].
+    pub zarr_format: u64,
+    /// Zarr node type; must be [NODE_TYPE_GROUP].
+    pub node_type: String,
     /// Zarr attributes with "ome" metadata.
-    pub attributes: OmeZarrGroupAttributes,
+    pub attributes: OmeZarrGroupAttributes<E>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-impl Validate for OmeZarrGroupMetadata {
+impl<E: Validate> Validate for OmeZarrGroupMetadata<E> {
     fn validate_inner(&self, accum: &mut Accumulator) {
+        validate_group_node(accum, self.zarr_format, &self.node_type);
         accum.validate_member_at("attributes", &self.attributes);
     }
 }
 
+impl<E> From<OmeZarrGroupAttributes<E>> for OmeZarrGroupMetadata<E> {
+    fn from(attributes: OmeZarrGroupAttributes<E>) -> Self {
+        Self::new(attributes)
+    }
+}
+
+impl<E> OmeZarrGroupMetadata<E> {
+    /// Group metadata with the given attributes and Zarr v3 group core metadata.
+    pub fn new(attributes: OmeZarrGroupAttributes<E>) -> Self {
+        Self {
+            zarr_format: ZARR_FORMAT,
+            node_type: NODE_TYPE_GROUP.to_string(),
+            attributes,
+            extra: Default::default(),
+        }
+    }
+}
+
+impl<E: Serialize + Validate> OmeZarrGroupMetadata<E> {
+    /// Serialise as the pretty-printed contents of a group's `zarr.json`.
+    ///
+    /// # Errors
+    /// Returns an error if the metadata is invalid, so that only conformant documents are written.
+    pub fn to_zarr_json(&self) -> Result<String, crate::Error> {
+        self.validate()?;
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
 /// Return the `ome` attribute from Zarr group metadata.
 ///
 /// # Errors
 /// Returns an error if:
 ///  - the `attributes`, `attributes.ome`, or `attributes.ome.version` keys do not exist, or
 ///  - the `attributes.ome.version` key is not equal to `"0.5"`.
-// TODO: Deprecate this
+#[deprecated(note = "deserialise `OmeZarrGroupMetadata` instead")]
 pub fn get_ome_attribute_from_zarr_group_metadata(
     group_metadata: &serde_json::Map<String, serde_json::Value>,
 ) -> Result<&serde_json::Value, serde_json::Error> {
@@ -172,4 +290,102 @@ mod tests {
             ..OmeFields::default()
         };
     }
+
+    #[test]
+    fn unknown_fields_roundtrip() {
+        let value = serde_json::json!({
+            "attributes": {
+                "ome": {
+                    "version": "0.5",
+                    "multiscales": [{
+                        "axes": [
+                            {"name": "y", "type": "space"},
+                            {"name": "x", "type": "space", "fiji:calibrated": true}
+                        ],
+                        "datasets": [{"path": "0", "coordinateTransformations": [
+                            {"type": "scale", "scale": [1.0, 1.0]}
+                        ]}],
+                        "provenance": {"tool": "example"}
+                    }],
+                    "vendor": 1
+                },
+                "other": "attribute"
+            },
+            "zarr_format": 3,
+            "node_type": "group"
+        });
+        let metadata: OmeZarrGroupMetadata = serde_json::from_value(value.clone()).unwrap();
+        metadata.validate().unwrap();
+        assert_eq!(metadata.attributes.ome.extra["vendor"], 1);
+        assert_eq!(metadata.attributes.extensions.0["other"], "attribute");
+        assert_eq!(serde_json::to_value(&metadata).unwrap(), value);
+    }
+
+    #[test]
+    fn group_document() {
+        let attributes: OmeZarrGroupAttributes = serde_json::from_value(serde_json::json!({
+            "ome": {"version": "0.5", "labels": ["cells"]}
+        }))
+        .unwrap();
+        let json = OmeZarrGroupMetadata::new(attributes)
+            .to_zarr_json()
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["zarr_format"], 3);
+        assert_eq!(value["node_type"], "group");
+        assert_eq!(value["attributes"]["ome"]["labels"][0], "cells");
+
+        let mut metadata: OmeZarrGroupMetadata = serde_json::from_value(value).unwrap();
+        metadata.validate().unwrap();
+        metadata.zarr_format = 2;
+        metadata.node_type = "array".into();
+        let err = metadata.to_zarr_json().unwrap_err().to_string();
+        assert!(
+            err.contains("zarr_format") && err.contains("node_type"),
+            "{err}"
+        );
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Deconvolution {
+        iterations: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct VendorExtensions {
+        #[serde(rename = "example-deconvolution")]
+        deconvolution: Deconvolution,
+    }
+
+    impl Validate for VendorExtensions {
+        fn validate_inner(&self, accum: &mut Accumulator) {
+            accum.with_key("example-deconvolution", |a| {
+                if self.deconvolution.iterations == 0 {
+                    a.add_failure_at("iterations", "must be positive");
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn typed_extensions() {
+        let attrs = |iterations: u64| {
+            serde_json::json!({
+                "ome": {"version": "0.5", "labels": ["cells"]},
+                "example-deconvolution": {"iterations": iterations}
+            })
+        };
+        let valid: OmeZarrGroupAttributes<VendorExtensions> =
+            serde_json::from_value(attrs(10)).unwrap();
+        valid.validate().unwrap();
+        assert_eq!(valid.extensions.deconvolution.iterations, 10);
+
+        let invalid: OmeZarrGroupAttributes<VendorExtensions> =
+            serde_json::from_value(attrs(0)).unwrap();
+        assert!(invalid.validate().is_err());
+
+        let raw: OmeZarrGroupAttributes = serde_json::from_value(attrs(0)).unwrap();
+        raw.validate().unwrap();
+        assert!(raw.extensions.0.contains_key("example-deconvolution"));
+    }
 }
@@ -0,0 +1,656 @@
+//! Semantic differences between two sets of OME-Zarr metadata.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde_json::Value;
+
+use super::{Axis, Channel, Color, MultiscaleImage, OmeFields, Omero, Plate, PlateWell, Window};
+
+/// A single difference between two sets of OME-Zarr metadata, in domain terms.
+///
+/// Multiscale images and channels are identified by their index,
+/// resolution levels by their dataset path, and wells by their name (e.g. `"B03"`).
+/// Anything without a more specific variant is reported as a [Change::Field],
+/// including changes to other fields of resolution levels and wells that are in both.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A multiscale image was added.
+    MultiscaleAdded {
+        /// Index of the multiscale image.
+        multiscale: usize,
+    },
+    /// A multiscale image was removed.
+    MultiscaleRemoved {
+        /// Index of the multiscale image.
+        multiscale: usize,
+    },
+    /// The axes of a multiscale image changed.
+    ///
+    /// Scales and translations are not compared when the axes change.
+    AxesChanged {
+        /// Index of the multiscale image.
+        multiscale: usize,
+        /// Axes before the change.
+        before: Vec<Axis>,
+        /// Axes after the change.
+        after: Vec<Axis>,
+    },
+    /// A resolution level was added.
+    LevelAdded {
+        /// Index of the multiscale image.
+        multiscale: usize,
+        /// Path of the level's dataset.
+        path: String,
+    },
+    /// A resolution level was removed.
+    LevelRemoved {
+        /// Index of the multiscale image.
+        multiscale: usize,
+        /// Path of the level's dataset.
+        path: String,
+    },
+    /// The effective scale of a resolution level changed along an axis.
+    LevelScaleChanged {
+        /// Index of the multiscale image.
+        multiscale: usize,
+        /// Path of the level's dataset.
+        path: String,
+        /// Name of the axis.
+        axis: String,
+        /// Scale before the change.
+        before: f64,
+        /// Scale after the change.
+        after: f64,
+    },
+    /// The effective translation of a resolution level changed along an axis.
+    LevelTranslationChanged {
+        /// Index of the multiscale image.
+        multiscale: usize,
+        /// Path of the level's dataset.
+        path: String,
+        /// Name of the axis.
+        axis: String,
+        /// Translation before the change.
+        before: f64,
+        /// Translation after the change.
+        after: f64,
+    },
+    /// An OMERO channel was added.
+    ChannelAdded {
+        /// Index and label of the channel (see [channel_name]).
+        channel: String,
+    },
+    /// An OMERO channel was removed.
+    ChannelRemoved {
+        /// Index and label of the channel (see [channel_name]).
+        channel: String,
+    },
+    /// The color of an OMERO channel changed.
+    ChannelColorChanged {
+        /// Index and label of the channel (see [channel_name]).
+        channel: String,
+        /// Color before the change.
+        before: Color,
+        /// Color after the change.
+        after: Color,
+    },
+    /// The window of an OMERO channel changed.
+    ChannelWindowChanged {
+        /// Index and label of the channel (see [channel_name]).
+        channel: String,
+        /// Window before the change.
+        before: Window,
+        /// Window after the change.
+        after: Window,
+    },
+    /// A well was added to the plate.
+    WellAdded {
+        /// Name of the well.
+        well: String,
+    },
+    /// A well was removed from the plate.
+    WellRemoved {
+        /// Name of the well.
+        well: String,
+    },
+    /// Any other field was added, removed, or changed.
+    Field {
+        /// `/`-separated path to the field within the `ome` metadata (e.g. `"/plate/name"`).
+        ///
+        /// Datasets and wells are keyed by their path and name rather than their index
+        /// (e.g. `"/multiscales/0/datasets/s0/extra"`, `"/plate/wells/B03/path"`).
+        path: String,
+        /// Value before the change, if the field existed.
+        before: Option<Value>,
+        /// Value after the change, if the field exists.
+        after: Option<Value>,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::MultiscaleAdded { multiscale } => write!(f, "multiscale {multiscale} added"),
+            Change::MultiscaleRemoved { multiscale } => {
+                write!(f, "multiscale {multiscale} removed")
+            }
+            Change::AxesChanged {
+                multiscale,
+                before,
+                after,
+            } => {
+                let names = |axes: &[Axis]| {
+                    axes.iter()
+                        .map(|a| a.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                write!(
+                    f,
+                    "multiscale {multiscale} axes changed from [{}] to [{}]",
+                    names(before),
+                    names(after)
+                )
+            }
+            Change::LevelAdded { multiscale, path } => {
+                write!(f, "multiscale {multiscale} level {path} added")
+            }
+            Change::LevelRemoved { multiscale, path } => {
+                write!(f, "multiscale {multiscale} level {path} removed")
+            }
+            Change::LevelScaleChanged {
+                multiscale,
+                path,
+                axis,
+                before,
+                after,
+            } => write!(
+                f,
+                "multiscale {multiscale} level {path} scale on {axis} changed from {before} to {after}"
+            ),
+            Change::LevelTranslationChanged {
+                multiscale,
+                path,
+                axis,
+                before,
+                after,
+            } => write!(
+                f,
+                "multiscale {multiscale} level {path} translation on {axis} changed from {before} to {after}"
+            ),
+            Change::ChannelAdded { channel } => write!(f, "channel {channel} added"),
+            Change::ChannelRemoved { channel } => write!(f, "channel {channel} removed"),
+            Change::ChannelColorChanged {
+                channel,
+                before,
+                after,
+            } => write!(
+                f,
+                "channel {channel} color changed from {before} to {after}"
+            ),
+            Change::ChannelWindowChanged {
+                channel,
+                before,
+                after,
+            } => write!(
+                f,
+                "channel {channel} window changed from [{}, {}] to [{}, {}]",
+                before.start, before.end, after.start, after.end
+            ),
+            Change::WellAdded { well } => write!(f, "well {well} added"),
+            Change::WellRemoved { well } => write!(f, "well {well} removed"),
+            Change::Field {
+                path,
+                before,
+                after,
+            } => match (before, after) {
+                (None, Some(a)) => write!(f, "{path} added: {a}"),
+                (Some(b), None) => write!(f, "{path} removed: {b}"),
+                (Some(b), Some(a)) => write!(f, "{path} changed from {b} to {a}"),
+                (None, None) => write!(f, "{path} unchanged"),
+            },
+        }
+    }
+}
+
+/// Name of an OMERO channel for reporting changes:
+/// its index, followed by its label in parentheses if it has one (e.g. `"0 (DAPI)"`).
+pub fn channel_name(index: usize, channel: &Channel) -> String {
+    match channel.other.get("label").and_then(Value::as_str) {
+        Some(label) => format!("{index} ({label})"),
+        None => index.to_string(),
+    }
+}
+
+/// Compare two JSON values, recording changes to leaf values and to arrays of different lengths.
+fn diff_values(path: &str, before: Option<&Value>, after: Option<&Value>, out: &mut Vec<Change>) {
+    match (before, after) {
+        (Some(Value::Object(b)), Some(Value::Object(a))) => {
+            for (key, bv) in b {
+                diff_values(&format!("{path}/{key}"), Some(bv), a.get(key), out);
+            }
+            for (key, av) in a.iter().filter(|(k, _)| !b.contains_key(*k)) {
+                diff_values(&format!("{path}/{key}"), None, Some(av), out);
+            }
+        }
+        (Some(Value::Array(b)), Some(Value::Array(a))) if b.len() == a.len() => {
+            for (idx, (bv, av)) in b.iter().zip(a).enumerate() {
+                diff_values(&format!("{path}/{idx}"), Some(bv), Some(av), out);
+            }
+        }
+        (b, a) if b != a => out.push(Change::Field {
+            path: path.to_string(),
+            before: b.cloned(),
+            after: a.cloned(),
+        }),
+        _ => (),
+    }
+}
+
+/// Compare the serialised forms of two values, ignoring the given keys.
+fn diff_remaining<T: serde::Serialize>(
+    path: &str,
+    before: &T,
+    after: &T,
+    ignore: &[&str],
+    out: &mut Vec<Change>,
+) {
+    let to_value = |v: &T| {
+        let mut value = serde_json::to_value(v).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            for key in ignore {
+                obj.remove(*key);
+            }
+        }
+        value
+    };
+    diff_values(path, Some(&to_value(before)), Some(&to_value(after)), out);
+}
+
+fn diff_optional<T: serde::Serialize>(
+    path: &str,
+    before: Option<&T>,
+    after: Option<&T>,
+    out: &mut Vec<Change>,
+) {
+    let before = before.map(|v| serde_json::to_value(v).unwrap_or_default());
+    let after = after.map(|v| serde_json::to_value(v).unwrap_or_default());
+    diff_values(path, before.as_ref(), after.as_ref(), out);
+}
+
+fn diff_multiscale(idx: usize, before: &MultiscaleImage, after: &MultiscaleImage) -> Vec<Change> {
+    let mut out = Vec::default();
+    let path = format!("/multiscales/{idx}");
+    diff_remaining(
+        &path,
+        before,
+        after,
+        &["axes", "datasets", "coordinateTransformations"],
+        &mut out,
+    );
+
+    if before.axes != after.axes {
+        out.push(Change::AxesChanged {
+            multiscale: idx,
+            before: before.axes.clone(),
+            after: after.axes.clone(),
+        });
+    }
+
+    let paths = |ms: &MultiscaleImage| -> BTreeMap<String, usize> {
+        ms.datasets
+            .iter()
+            .enumerate()
+            .map(|(i, ds)| (ds.path.clone(), i))
+            .collect()
+    };
+    let (before_paths, after_paths) = (paths(before), paths(after));
+    for ds in before.datasets.iter() {
+        if !after_paths.contains_key(&ds.path) {
+            out.push(Change::LevelRemoved {
+                multiscale: idx,
+                path: ds.path.clone(),
+            });
+        }
+    }
+    for ds in after.datasets.iter() {
+        if !before_paths.contains_key(&ds.path) {
+            out.push(Change::LevelAdded {
+                multiscale: idx,
+                path: ds.path.clone(),
+            });
+        }
+    }
+
+    let transforms = if before.axes == after.axes {
+        before
+            .level_transforms()
+            .ok()
+            .zip(after.level_transforms().ok())
+    } else {
+        None
+    };
+    if transforms.is_none() {
+        // compare the transformations as they are written instead
+        diff_optional(
+            &format!("{path}/coordinateTransformations"),
+            before.coordinate_transformations.as_ref(),
+            after.coordinate_transformations.as_ref(),
+            &mut out,
+        );
+    }
+
+    for (ds_path, b_idx) in before_paths.iter() {
+        let Some(a_idx) = after_paths.get(ds_path) else {
+            continue;
+        };
+        let (b_ds, a_ds) = (&before.datasets[*b_idx], &after.datasets[*a_idx]);
+        let Some((before_tfs, after_tfs)) = transforms.as_ref() else {
+            diff_remaining(
+                &format!("{path}/datasets/{ds_path}"),
+                b_ds,
+                a_ds,
+                &[],
+                &mut out,
+            );
+            continue;
+        };
+        let (b, a) = (&before_tfs[*b_idx], &after_tfs[*a_idx]);
+        for (axis_idx, axis) in after.axes.iter().enumerate() {
+            if b.scale[axis_idx] != a.scale[axis_idx] {
+                out.push(Change::LevelScaleChanged {
+                    multiscale: idx,
+                    path: ds_path.clone(),
+                    axis: axis.name.clone(),
+                    before: b.scale[axis_idx],
+                    after: a.scale[axis_idx],
+                });
+            }
+            if b.translation[axis_idx] != a.translation[axis_idx] {
+                out.push(Change::LevelTranslationChanged {
+                    multiscale: idx,
+                    path: ds_path.clone(),
+                    axis: axis.name.clone(),
+                    before: b.translation[axis_idx],
+                    after: a.translation[axis_idx],
+                });
+            }
+        }
+        diff_remaining(
+            &format!("{path}/datasets/{ds_path}"),
+            b_ds,
+            a_ds,
+            &["coordinateTransformations"],
+            &mut out,
+        );
+    }
+
+    // levels in both, in the order in which they are listed
+    let order = |ms: &MultiscaleImage, others: &BTreeMap<String, usize>| -> Vec<String> {
+        ms.datasets
+            .iter()
+            .map(|ds| ds.path.clone())
+            .filter(|p| others.contains_key(p))
+            .collect()
+    };
+    let (before_order, after_order) = (order(before, &after_paths), order(after, &before_paths));
+    if before_order != after_order {
+        out.push(Change::Field {
+            path: format!("{path}/datasets"),
+            before: Some(before_order.into()),
+            after: Some(after_order.into()),
+        });
+    }
+    out
+}
+
+fn diff_omero(before: &Omero, after: &Omero, out: &mut Vec<Change>) {
+    diff_remaining("/omero", before, after, &["channels"], out);
+    for (idx, (b, a)) in before.channels.iter().zip(&after.channels).enumerate() {
+        let channel = channel_name(idx, a);
+        if b.color != a.color {
+            out.push(Change::ChannelColorChanged {
+                channel: channel.clone(),
+                before: b.color,
+                after: a.color,
+            });
+        }
+        if b.window != a.window {
+            out.push(Change::ChannelWindowChanged {
+                channel,
+                before: b.window.clone(),
+                after: a.window.clone(),
+            });
+        }
+        diff_values(
+            &format!("/omero/channels/{idx}"),
+            Some(&Value::Object(b.other.clone())),
+            Some(&Value::Object(a.other.clone())),
+            out,
+        );
+    }
+    let common = before.channels.len().min(after.channels.len());
+    for (idx, ch) in before.channels.iter().enumerate().skip(common) {
+        out.push(Change::ChannelRemoved {
+            channel: channel_name(idx, ch),
+        });
+    }
+    for (idx, ch) in after.channels.iter().enumerate().skip(common) {
+        out.push(Change::ChannelAdded {
+            channel: channel_name(idx, ch),
+        });
+    }
+}
+
+fn diff_plate(before: &Plate, after: &Plate, out: &mut Vec<Change>) {
+    diff_remaining("/plate", before, after, &["wells"], out);
+    let wells = |plate: &Plate| -> Vec<(String, PlateWell)> {
+        plate
+            .wells
+            .iter()
+            .map(|w| {
+                let name = plate
+                    .well_name(w.row_index, w.column_index)
+                    .unwrap_or_else(|| w.path.to_string_lossy().into_owned());
+                (name, w.clone())
+            })
+            .collect()
+    };
+    let (before_wells, after_wells) = (wells(before), wells(after));
+    let by_name = |wells: &[(String, PlateWell)]| -> BTreeMap<String, usize> {
+        wells
+            .iter()
+            .enumerate()
+            .map(|(idx, (name, _))| (name.clone(), idx))
+            .collect()
+    };
+    let (before_names, after_names) = (by_name(&before_wells), by_name(&after_wells));
+    for (well, _) in before_wells
+        .iter()
+        .filter(|(n, _)| !after_names.contains_key(n))
+    {
+        out.push(Change::WellRemoved { well: well.clone() });
+    }
+    for (well, _) in after_wells
+        .iter()
+        .filter(|(n, _)| !before_names.contains_key(n))
+    {
+        out.push(Change::WellAdded { well: well.clone() });
+    }
+    for (well, b) in before_wells.iter() {
+        if let Some(a_idx) = after_names.get(well) {
+            diff_remaining(
+                &format!("/plate/wells/{well}"),
+                b,
+                &after_wells[*a_idx].1,
+                &[],
+                out,
+            );
+        }
+    }
+}
+
+/// Semantic differences from `before` to `after`.
+///
+/// Resolution levels are compared by their effective scale and translation
+/// (see [MultiscaleImage::level_transforms]), so equivalent ways of writing
+/// the same transformations are not reported.
+pub fn diff(before: &OmeFields, after: &OmeFields) -> Vec<Change> {
+    let mut out = Vec::default();
+    diff_remaining(
+        "",
+        before,
+        after,
+        &["multiscales", "omero", "plate"],
+        &mut out,
+    );
+
+    let empty = Vec::default();
+    let before_ms = before.multiscales.as_ref().unwrap_or(&empty);
+    let after_ms = after.multiscales.as_ref().unwrap_or(&empty);
+    for (idx, (b, a)) in before_ms.iter().zip(after_ms).enumerate() {
+        out.extend(diff_multiscale(idx, b, a));
+    }
+    let common = before_ms.len().min(after_ms.len());
+    out.extend(
+        (common..before_ms.len()).map(|multiscale| Change::MultiscaleRemoved { multiscale }),
+    );
+    out.extend((common..after_ms.len()).map(|multiscale| Change::MultiscaleAdded { multiscale }));
+
+    match (before.omero.as_ref(), after.omero.as_ref()) {
+        (Some(b), Some(a)) => diff_omero(b, a, &mut out),
+        (b, a) => diff_optional("/omero", b, a, &mut out),
+    }
+    match (before.plate.as_ref(), after.plate.as_ref()) {
+        (Some(b), Some(a)) => diff_plate(b, a, &mut out),
+        (b, a) => diff_optional("/plate", b, a, &mut out),
+    }
+    out
+}
+
+impl OmeFields {
+    /// Semantic differences from this metadata to `other` (see [diff]).
+    pub fn diff(&self, other: &Self) -> Vec<Change> {
+        diff(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn fields(x_scale: f64, color: &str, wells: &[(&str, usize)]) -> OmeFields {
+        serde_json::from_value(json!({
+            "version": "0.5",
+            "multiscales": [{
+                "name": "cells",
+                "axes": [{"name": "y", "type": "space"}, {"name": "x", "type": "space"}],
+                "datasets": [
+                    {"path": "0", "coordinateTransformations": [
+                        {"type": "scale", "scale": [0.25, x_scale / 2.0]}
+                    ]},
+                    {"path": "1", "coordinateTransformations": [
+                        {"type": "scale", "scale": [0.5, x_scale]}
+                    ]}
+                ]
+            }],
+            "omero": {"channels": [{"color": color, "label": "DAPI",
+                "window": {"min": 0.0, "max": 255.0, "start": 0.0, "end": 255.0}}]},
+            "plate": {
+                "columns": [{"name": "01"}, {"name": "02"}, {"name": "03"}],
+                "rows": [{"name": "A"}, {"name": "B"}],
+                "wells": wells.iter().map(|(p, c)| json!({
+                    "path": p, "rowIndex": 1, "columnIndex": c
+                })).collect::<Vec<_>>()
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn semantic_changes() {
+        let before = fields(0.5, "0000FF", &[("B/01", 0), ("B/03", 2)]);
+        let after = fields(0.52, "00FF00", &[("B/01", 0)]);
+        assert!(diff(&before, &before).is_empty());
+
+        let changes: Vec<String> = before.diff(&after).iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            changes,
+            vec![
+                "multiscale 0 level 0 scale on x changed from 0.25 to 0.26",
+                "multiscale 0 level 1 scale on x changed from 0.5 to 0.52",
+                "channel 0 (DAPI) color changed from 0000FF to 00FF00",
+                "well B03 removed",
+            ]
+        );
+    }
+
+    #[test]
+    fn matched_levels_and_wells() {
+        let before = fields(0.5, "0000FF", &[("B/01", 0)]);
+        let mut after = before.clone();
+        let ms = &mut after.multiscales.as_mut().unwrap()[0];
+        ms.datasets[1].extra.insert("note".into(), json!("binned"));
+        ms.datasets.swap(0, 1);
+        let plate = after.plate.as_mut().unwrap();
+        plate.wells[0].path = "B/1".into();
+        plate.wells[0].extra.insert("note".into(), json!("empty"));
+
+        assert_eq!(
+            diff(&before, &after),
+            vec![
+                Change::Field {
+                    path: "/multiscales/0/datasets/1/note".into(),
+                    before: None,
+                    after: Some(json!("binned")),
+                },
+                Change::Field {
+                    path: "/multiscales/0/datasets".into(),
+                    before: Some(json!(["0", "1"])),
+                    after: Some(json!(["1", "0"])),
+                },
+                Change::Field {
+                    path: "/plate/wells/B01/path".into(),
+                    before: Some(json!("B/01")),
+                    after: Some(json!("B/1")),
+                },
+                Change::Field {
+                    path: "/plate/wells/B01/note".into(),
+                    before: None,
+                    after: Some(json!("empty")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn other_fields() {
+        let before = fields(0.5, "0000FF", &[]);
+        let mut after = before.clone();
+        after.labels = Some(vec!["cells".into()]);
+        after.multiscales.as_mut().unwrap()[0].name = Some("nuclei".into());
+        after.multiscales.as_mut().unwrap()[0].datasets.pop();
+
+        assert_eq!(
+            diff(&before, &after),
+            vec![
+                Change::Field {
+                    path: "/labels".into(),
+                    before: None,
+                    after: Some(json!(["cells"])),
+                },
+                Change::Field {
+                    path: "/multiscales/0/name".into(),
+                    before: Some(json!("cells")),
+                    after: Some(json!("nuclei")),
+                },
+                Change::LevelRemoved {
+                    multiscale: 0,
+                    path: "1".into(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Physical extent of the resolution levels of a multiscale image.
+
+use crate::{
+    Error, NDim, Result, v0_4::coordinate_transformations::compose_transforms,
+    view::MultiscaleImageView,
+};
+
+use super::{AxisUnit, MultiscaleImage};
+
+/// Effective transformation of a resolution level from array indices to physical coordinates,
+/// combining the dataset-level and multiscale-level `coordinateTransformations`.
+///
+/// For each axis, `physical = scale * index + translation`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelTransform {
+    /// Physical size of a voxel along each axis.
+    pub scale: Vec<f64>,
+    /// Physical coordinate of the voxel at index 0 along each axis.
+    pub translation: Vec<f64>,
+}
+
+impl LevelTransform {
+    /// Physical coordinate of the given array index.
+    pub fn to_physical(&self, index: &[f64]) -> Vec<f64> {
+        index
+            .iter()
+            .zip(self.scale.iter().zip(self.translation.iter()))
+            .map(|(i, (s, t))| s * i + t)
+            .collect()
+    }
+}
+
+/// Physical extent of a resolution level along a single axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisExtent {
+    /// Name of the axis.
+    pub name: String,
+    /// Physical unit of the axis, if given.
+    pub unit: Option<AxisUnit>,
+    /// Physical coordinate of the voxel at index 0.
+    pub origin: f64,
+    /// Physical size of a single voxel.
+    pub voxel_size: f64,
+    /// Physical length covered by all voxels along this axis.
+    pub extent: f64,
+}
+
+impl AxisExtent {
+    /// Physical coordinate one voxel past the last voxel along this axis.
+    pub fn end(&self) -> f64 {
+        self.origin + self.extent
+    }
+}
+
+/// Physical extent of a single resolution level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelExtent {
+    /// Path to the array for this resolution level.
+    pub path: String,
+    /// Shape of the array for this resolution level.
+    pub shape: Vec<u64>,
+    /// Per-axis extent, in the same order as the multiscale image's axes.
+    pub axes: Vec<AxisExtent>,
+}
+
+impl LevelExtent {
+    /// Physical coordinate of the voxel at index 0.
+    pub fn origin(&self) -> Vec<f64> {
+        self.axes.iter().map(|a| a.origin).collect()
+    }
+
+    /// Physical size of a single voxel.
+    pub fn voxel_size(&self) -> Vec<f64> {
+        self.axes.iter().map(|a| a.voxel_size).collect()
+    }
+
+    /// Minimum and maximum physical coordinates covered by this resolution level.
+    pub fn bounding_box(&self) -> (Vec<f64>, Vec<f64>) {
+        self.axes
+            .iter()
+            .map(|a| (a.origin.min(a.end()), a.origin.max(a.end())))
+            .unzip()
+    }
+}
+
+/// See [MultiscaleImage::level_transforms]; for multiscale images of any version.
+pub(crate) fn level_transforms(image: &dyn MultiscaleImageView) -> Result<Vec<LevelTransform>> {
+    let ndim = image.axes().len();
+    let global = image.coordinate_transformations().unwrap_or_default();
+    image
+        .datasets()
+        .iter()
+        .map(|ds| {
+            let (scale, translation) =
+                compose_transforms(ndim, ds.coordinate_transformations.iter().chain(global))?;
+            Ok(LevelTransform { scale, translation })
+        })
+        .collect()
+}
+
+impl MultiscaleImage {
+    /// Effective transformation of each resolution level, in the same order as `datasets`.
+    ///
+    /// # Errors
+    /// Returns an error if any transformation is stored at a path,
+    /// or has the wrong dimensionality.
+    pub fn level_transforms(&self) -> Result<Vec<LevelTransform>> {
+        level_transforms(self)
+    }
+
+    /// Physical extent of each resolution level, given the shape of each level's array
+    /// in the same order as `datasets`.
+    ///
+    /// # Errors
+    /// Returns an error if the number of shapes does not match the number of datasets,
+    /// if any shape has the wrong dimensionality,
+    /// or if the transformations cannot be resolved (see [MultiscaleImage::level_transforms]).
+    pub fn level_extents<S: AsRef<[u64]>>(&self, shapes: &[S]) -> Result<Vec<LevelExtent>> {
+        if shapes.len() != self.datasets.len() {
+            return Err(Error::general(format!(
+                "got {} shapes for {} datasets",
+                shapes.len(),
+                self.datasets.len()
+            )));
+        }
+        let ndim = self.ndim();
+        self.level_transforms()?
+            .into_iter()
+            .zip(self.datasets.iter().zip(shapes))
+            .map(|(tf, (ds, shape))| {
+                let shape = shape.as_ref();
+                if shape.len() != ndim {
+                    return Err(Error::NDimMismatch {
+                        expected: ndim,
+                        actual: shape.len(),
+                    });
+                }
+                let axes = self
+                    .axes
+                    .iter()
+                    .zip(shape)
+                    .zip(tf.scale.iter().zip(tf.translation.iter()))
+                    .map(|((ax, len), (scale, translation))| AxisExtent {
+                        name: ax.name.clone(),
+                        unit: ax.unit.clone(),
+                        origin: *translation,
+                        voxel_size: *scale,
+                        extent: *scale * *len as f64,
+                    })
+                    .collect();
+                Ok(LevelExtent {
+                    path: ds.path.clone(),
+                    shape: shape.to_vec(),
+                    axes,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> MultiscaleImage {
+        serde_json::from_str(
+            r#"{
+                "axes": [
+                    {"name": "c", "type": "channel"},
+                    {"name": "y", "type": "space", "unit": "micrometer"},
+                    {"name": "x", "type": "space", "unit": "micrometer"}
+                ],
+                "datasets": [
+                    {"path": "0", "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 0.5, 0.5]}
+                    ]},
+                    {"path": "1", "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 1.0, 1.0]},
+                        {"type": "translation", "translation": [0.0, 0.25, 0.25]}
+                    ]}
+                ],
+                "coordinateTransformations": [
+                    {"type": "scale", "scale": [1.0, 2.0, 2.0]},
+                    {"type": "translation", "translation": [0.0, 10.0, -10.0]}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn level_transforms() {
+        let tfs = image().level_transforms().unwrap();
+        assert_eq!(tfs[0].scale, vec![1.0, 1.0, 1.0]);
+        assert_eq!(tfs[0].translation, vec![0.0, 10.0, -10.0]);
+        assert_eq!(tfs[1].scale, vec![1.0, 2.0, 2.0]);
+        assert_eq!(tfs[1].translation, vec![0.0, 10.5, -9.5]);
+        assert_eq!(tfs[1].to_physical(&[0.0, 1.0, 2.0]), vec![0.0, 12.5, -5.5]);
+    }
+
+    #[test]
+    fn level_extents() {
+        let extents = image()
+            .level_extents(&[[3, 100, 200], [3, 50, 100]])
+            .unwrap();
+        assert_eq!(extents[1].path, "1");
+        assert_eq!(extents[1].voxel_size(), vec![1.0, 2.0, 2.0]);
+        let (min, max) = extents[1].bounding_box();
+        assert_eq!(min, vec![0.0, 10.5, -9.5]);
+        assert_eq!(max, vec![3.0, 110.5, 190.5]);
+        assert!(matches!(
+            extents[1].axes[2].unit,
+            Some(AxisUnit::Space(crate::v0_5::AxisUnitSpace::Micrometer))
+        ));
+    }
+
+    #[test]
+    fn level_extents_wrong_ndim() {
+        assert!(matches!(
+            image().level_extents(&[vec![100, 200], vec![50, 100]]),
+            Err(Error::NDimMismatch {
+                expected: 3,
+                actual: 2
+            })
+        ));
+    }
+}
@@ -2,16 +2,32 @@
 //!
 //! <https://ngff.openmicroscopy.org/0.5/#labels-md>.
 
-use crate::v0_4::validate_unique_labels;
 #[doc(inline)]
-pub use crate::v0_4::{ImageLabelColor, ImageLabelProperties, ImageLabelSource, Labels};
+pub use crate::v0_4::{
+    ImageLabelColor, ImageLabelProperties, ImageLabelSource, LABEL_VALUE_COLUMN, LabelColorTable,
+    LabelPropertiesTable, Labels, Palette, PropertyColumn, PropertyType,
+};
+use crate::{
+    hierarchy::{GroupReferences, HierarchyGroup, split_parent},
+    v0_4::validate_unique_labels,
+    view::{ImageLabelView, MultiscaleImageView},
+};
 
 use serde::{Deserialize, Serialize};
-use validatrix::Validate;
+use validatrix::{Accumulator, Validate};
+
+use super::level_transforms;
+
+/// Default path of a label image's source image, relative to the label image group.
+///
+/// Label images are usually stored in a `labels` group nested within the source image group.
+pub const DEFAULT_LABEL_SOURCE: &str = "../../";
+
+/// Relative tolerance for comparing the scales of a label image and its source image.
+const SCALE_TOLERANCE: f64 = 1e-6;
 
 /// `image-label` metadata. Stores information about the display colors, source image, and optionally, further arbitrary properties of a label image.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ImageLabel {
     /// Describes the color information for the unique label values.
     pub colors: Option<Vec<ImageLabelColor>>,
@@ -21,6 +37,9 @@ pub struct ImageLabel {
     /// Information about the original image from which the label image derives (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<ImageLabelSource>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for ImageLabel {
@@ -45,19 +64,130 @@ impl Validate for ImageLabel {
     }
 }
 
+impl ImageLabel {
+    /// Indexed lookup of this label image's `colors`.
+    pub fn color_table(&self) -> LabelColorTable {
+        self.colors.iter().flatten().collect()
+    }
+
+    /// Columnar view of this label image's `properties`.
+    pub fn properties_table(&self) -> LabelPropertiesTable {
+        self.properties.iter().flatten().collect()
+    }
+}
+
+/// Validate a label image group's references to its source image and parent `labels` group.
+pub(crate) fn validate_label_in_hierarchy<T: GroupReferences>(
+    accum: &mut Accumulator,
+    group: &HierarchyGroup<'_, T>,
+    label: &dyn ImageLabelView,
+) {
+    let label_ms = group.metadata.multiscale();
+    if label_ms.is_none() {
+        accum.add_failure("label image group has no multiscales");
+    }
+
+    let source = label
+        .source()
+        .and_then(|s| s.image.as_ref())
+        .map(|p| p.to_string_lossy())
+        .unwrap_or(DEFAULT_LABEL_SOURCE.into());
+    accum.with_keys(&["source".into(), "image".into()], |a| {
+        let Some((path, metadata)) = group.relative(&source) else {
+            a.add_failure(format!("path {source:?} is outside the hierarchy"));
+            return;
+        };
+        let Some(source_ms) = metadata.and_then(|m| m.multiscale()) else {
+            a.add_failure(format!("no multiscale image at {path:?}"));
+            return;
+        };
+        if let Some(label_ms) = label_ms {
+            validate_label_matches_source(a, label_ms, source_ms);
+        }
+    });
+
+    if let Some((parent_path, name)) = split_parent(group.path) {
+        let listed = group
+            .hierarchy
+            .get(parent_path)
+            .and_then(|p| p.labels())
+            .is_some_and(|labels| labels.iter().any(|l| l == name));
+        if !listed {
+            accum.add_failure(format!(
+                "label image {name:?} is not listed in the labels of parent group {parent_path:?}"
+            ));
+        }
+    }
+}
+
+fn validate_label_matches_source(
+    accum: &mut Accumulator,
+    label: &dyn MultiscaleImageView,
+    source: &dyn MultiscaleImageView,
+) {
+    if label.axes().len() != source.axes().len() {
+        accum.add_failure(format!(
+            "label image has {} axes, source image has {}",
+            label.axes().len(),
+            source.axes().len()
+        ));
+    } else {
+        for (idx, (l, s)) in label.axes().iter().zip(source.axes().iter()).enumerate() {
+            if l.name != s.name || l.r#type != s.r#type || l.unit != s.unit {
+                accum.add_failure(format!(
+                    "label image axis {idx} ({:?}) does not match source image axis ({:?}) by name, type, and unit",
+                    l.name, s.name
+                ));
+            }
+        }
+    }
+
+    if label.datasets().len() != source.datasets().len() {
+        accum.add_failure(format!(
+            "label image has {} resolution levels, source image has {}",
+            label.datasets().len(),
+            source.datasets().len()
+        ));
+        return;
+    }
+
+    // unresolvable transforms are reported by the images' own validation
+    let (Ok(label_tfs), Ok(source_tfs)) = (level_transforms(label), level_transforms(source))
+    else {
+        return;
+    };
+    for (idx, (l, s)) in label_tfs.iter().zip(source_tfs.iter()).enumerate() {
+        let compatible = l.scale.len() == s.scale.len()
+            && l.scale
+                .iter()
+                .zip(s.scale.iter())
+                .all(|(l, s)| (l - s).abs() <= SCALE_TOLERANCE * l.abs().max(s.abs()));
+        if !compatible {
+            accum.add_failure(format!(
+                "label image level {idx} has scale {:?}, source image has {:?}",
+                l.scale, s.scale
+            ));
+        }
+    }
+}
+
 impl From<crate::v0_4::ImageLabel> for ImageLabel {
     fn from(value: crate::v0_4::ImageLabel) -> Self {
         Self {
             colors: value.colors,
             properties: value.properties,
             source: value.source,
+            extra: value.extra,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::v0_5::OmeZarrGroupMetadata;
+    use crate::{
+        Hierarchy,
+        v0_5::{OmeFields, OmeZarrGroupMetadata},
+    };
 
     use super::*;
 
@@ -70,4 +200,49 @@ mod tests {
         let ome_metadata: OmeZarrGroupMetadata = serde_json::from_str(json).unwrap();
         let _image_label: ImageLabel = ome_metadata.attributes.ome.image_label.unwrap();
     }
+
+    fn image_fields(scale: f64, label: Option<&str>) -> OmeFields {
+        serde_json::from_value(serde_json::json!({
+            "version": "0.5",
+            "multiscales": [{
+                "axes": [
+                    {"name": "y", "type": "space", "unit": "micrometer"},
+                    {"name": "x", "type": "space", "unit": "micrometer"}
+                ],
+                "datasets": [{"path": "0", "coordinateTransformations": [
+                    {"type": "scale", "scale": [scale, scale]}
+                ]}]
+            }],
+            "image-label": label.map(|source| serde_json::json!({"source": {"image": source}})),
+        }))
+        .unwrap()
+    }
+
+    fn hierarchy(label_scale: f64, listed: &str) -> Hierarchy<OmeFields> {
+        let labels: OmeFields = serde_json::from_value(serde_json::json!({
+            "version": "0.5",
+            "labels": [listed],
+        }))
+        .unwrap();
+        Hierarchy::try_from_iter([
+            ("img".to_string(), image_fields(0.5, None)),
+            ("img/labels".to_string(), labels),
+            (
+                "img/labels/seg".to_string(),
+                image_fields(label_scale, Some("../../")),
+            ),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn label_in_hierarchy() {
+        let valid = hierarchy(0.5, "seg");
+        assert!(valid.validate_groups().is_empty());
+
+        let invalid = hierarchy(1.0, "other");
+        let errors = invalid.validate_groups();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors["img/labels/seg"].len(), 2);
+    }
 }
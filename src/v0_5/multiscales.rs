@@ -3,15 +3,18 @@
 //! <https://ngff.openmicroscopy.org/0.5/#multiscale-md>.
 
 use serde::{Deserialize, Serialize};
-use validatrix::{Accumulator, Validate};
+use validatrix::{Accumulator, Validate, ValidateContext};
 
 use crate::{MaybeNDim, NDim};
 
-use super::{Axis, CoordinateTransform, MultiscaleImageDataset, MultiscaleImageMetadata};
-use crate::v0_4::multiscales::{valid_axes, valid_datasets, valid_transforms};
+use super::{
+    Axis, CoordinateTransform, DownsamplingType, MultiscaleImageDataset, MultiscaleImageMetadata,
+    PyramidChecks,
+};
+use crate::v0_4::multiscales::{valid_axes, valid_datasets, valid_pyramid, valid_transforms};
 
 /// `multiscales` element metadata. Describes a multiscale image.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MultiscaleImage {
     /// The name of the multiscale image (optional).
@@ -31,6 +34,9 @@ pub struct MultiscaleImage {
     /// A dictionary with additional information about the downscaling method (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<MultiscaleImageMetadata>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for MultiscaleImage {
@@ -49,6 +55,33 @@ impl Validate for MultiscaleImage {
     }
 }
 
+impl MultiscaleImage {
+    /// The [`type`](MultiscaleImage::type) of downscaling method, if given.
+    pub fn downsampling_type(&self) -> Option<DownsamplingType> {
+        self.r#type.as_deref().map(DownsamplingType::from)
+    }
+
+    /// Set the [`type`](MultiscaleImage::type) of downscaling method.
+    pub fn set_downsampling_type(&mut self, downsampling_type: DownsamplingType) {
+        self.r#type = Some(downsampling_type.to_string());
+    }
+}
+
+impl ValidateContext for MultiscaleImage {
+    type Context = PyramidChecks;
+
+    fn validate_inner(&self, context: &Self::Context, accum: &mut Accumulator) {
+        Validate::validate_inner(self, accum);
+        valid_pyramid(
+            accum,
+            context,
+            &self.axes,
+            self.coordinate_transformations.as_deref(),
+            &self.datasets,
+        );
+    }
+}
+
 impl NDim for MultiscaleImage {
     fn ndim(&self) -> usize {
         self.axes.len()
@@ -64,6 +97,7 @@ impl From<crate::v0_4::MultiscaleImage> for MultiscaleImage {
             coordinate_transformations: value.coordinate_transformations,
             r#type: value.r#type,
             metadata: value.metadata,
+            extra: value.extra,
         }
     }
 }
@@ -96,4 +130,57 @@ mod tests {
         let ome_metadata: OmeZarrGroupMetadata = serde_json::from_str(json).unwrap();
         let _multiscales: Vec<MultiscaleImage> = ome_metadata.attributes.ome.multiscales.unwrap();
     }
+
+    fn image(scales: &[[f64; 3]], translation: [f64; 3]) -> MultiscaleImage {
+        let datasets: Vec<_> = scales
+            .iter()
+            .enumerate()
+            .map(|(idx, s)| {
+                serde_json::json!({
+                    "path": idx.to_string(),
+                    "coordinateTransformations": [
+                        {"type": "scale", "scale": s},
+                        {"type": "translation", "translation": translation},
+                    ]
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "axes": [
+                {"name": "c", "type": "channel"},
+                {"name": "y", "type": "space"},
+                {"name": "x", "type": "space"}
+            ],
+            "datasets": datasets,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn datasets_out_of_order() {
+        let img = image(&[[1.0, 1.0, 1.0], [1.0, 0.5, 0.5]], [0.0; 3]);
+        assert!(Validate::validate(&img).is_err());
+    }
+
+    #[test]
+    fn pyramid_checks() {
+        let checks = PyramidChecks::default();
+        let consistent = image(
+            &[[1.0, 1.0, 1.0], [1.0, 2.0, 2.0], [1.0, 4.0, 4.0]],
+            [0.0; 3],
+        );
+        assert!(ValidateContext::validate(&consistent, &checks).is_ok());
+
+        let inconsistent = image(
+            &[[1.0, 1.0, 1.0], [1.0, 2.0, 2.0], [1.0, 3.0, 4.0]],
+            [0.0; 3],
+        );
+        assert!(Validate::validate(&inconsistent).is_ok());
+        assert!(ValidateContext::validate(&inconsistent, &checks).is_err());
+
+        let channel = image(&[[2.0, 1.0, 1.0]], [1.0, 0.0, 0.0]);
+        assert!(Validate::validate(&channel).is_ok());
+        let err = ValidateContext::validate(&channel, &checks).unwrap_err();
+        assert_eq!(err.len(), 2);
+    }
 }
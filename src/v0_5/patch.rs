@@ -0,0 +1,160 @@
+//! Editing group attributes with JSON Patch ([RFC 6902](https://www.rfc-editor.org/rfc/rfc6902))
+//! and JSON Merge Patch ([RFC 7396](https://www.rfc-editor.org/rfc/rfc7396)), with re-validation.
+
+use json_patch::Patch;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use validatrix::{Valid, Validate};
+
+use super::OmeZarrGroupAttributes;
+use crate::{Error, Result};
+
+fn parse<E: DeserializeOwned + Validate>(doc: Value) -> Result<Valid<OmeZarrGroupAttributes<E>>> {
+    let attributes: OmeZarrGroupAttributes<E> = serde_json::from_value(doc)?;
+    Ok(Valid::try_new(attributes)?)
+}
+
+/// Apply a JSON Patch to valid group attributes, returning the patched attributes if they are valid.
+///
+/// Intermediate documents may be invalid, e.g. when one operation removes a field
+/// and a later one adds its replacement; only the final document must be valid.
+///
+/// # Errors
+/// Returns [Error::JsonPatch] if an operation cannot be applied (including a failed `test`),
+/// in which case no operations are applied.
+///
+/// Returns [Error::PatchInvalid] if the patched attributes cannot be deserialised or are invalid.
+/// It names the earliest operation after which the document never becomes valid again,
+/// and gives the error for the final document.
+pub fn apply_json_patch<E>(
+    attributes: &Valid<OmeZarrGroupAttributes<E>>,
+    patch: &Patch,
+) -> Result<Valid<OmeZarrGroupAttributes<E>>>
+where
+    E: Serialize + DeserializeOwned + Validate,
+{
+    let original = serde_json::to_value(attributes.inner())?;
+    let mut doc = original.clone();
+    json_patch::patch(&mut doc, &patch.0)?;
+    let source = match parse(doc) {
+        Ok(valid) => return Ok(valid),
+        Err(e) => Box::new(e),
+    };
+
+    // the original is valid, so some operation broke it: replay to find which
+    let mut doc = original;
+    let mut operation = 0;
+    for (idx, op) in patch.0.iter().enumerate() {
+        json_patch::patch(&mut doc, std::slice::from_ref(op))?;
+        if parse::<E>(doc.clone()).is_ok() {
+            operation = idx + 1;
+        }
+    }
+    Err(Error::PatchInvalid { operation, source })
+}
+
+/// Apply a JSON Merge Patch to valid group attributes, returning the patched attributes if they are valid.
+///
+/// # Errors
+/// Returns [Error::PatchInvalid] if the patched attributes cannot be deserialised or are invalid.
+/// The merge patch is treated as a single operation at index 0.
+pub fn apply_merge_patch<E>(
+    attributes: &Valid<OmeZarrGroupAttributes<E>>,
+    patch: &Value,
+) -> Result<Valid<OmeZarrGroupAttributes<E>>>
+where
+    E: Serialize + DeserializeOwned + Validate,
+{
+    let mut doc = serde_json::to_value(attributes.inner())?;
+    json_patch::merge(&mut doc, patch);
+    parse(doc).map_err(|e| Error::PatchInvalid {
+        operation: 0,
+        source: Box::new(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn attributes() -> Valid<OmeZarrGroupAttributes> {
+        serde_json::from_value(json!({
+            "ome": {
+                "version": "0.5",
+                "multiscales": [{
+                    "axes": [{"name": "y", "type": "space"}, {"name": "x", "type": "space"}],
+                    "datasets": [{"path": "0", "coordinateTransformations": [
+                        {"type": "scale", "scale": [0.5, 0.5]}
+                    ]}]
+                }]
+            },
+            "other": {"key": "value"}
+        }))
+        .unwrap()
+    }
+
+    fn patch(ops: Value) -> Patch {
+        serde_json::from_value(ops).unwrap()
+    }
+
+    #[test]
+    fn json_patch() {
+        let attrs = attributes();
+        let patched = apply_json_patch(
+            &attrs,
+            &patch(json!([
+                {"op": "remove", "path": "/ome/multiscales/0/axes/1"},
+                {"op": "add", "path": "/ome/multiscales/0/axes/-", "value": {"name": "x", "type": "space", "unit": "micrometer"}},
+                {"op": "replace", "path": "/other/key", "value": "changed"},
+            ])),
+        )
+        .unwrap();
+        assert!(
+            patched.ome.multiscales.as_ref().unwrap()[0].axes[1]
+                .unit
+                .is_some()
+        );
+        assert_eq!(patched.extensions.0["other"]["key"], "changed");
+
+        let Err(Error::PatchInvalid { operation, .. }) = apply_json_patch(
+            &attrs,
+            &patch(json!([
+                {"op": "add", "path": "/ome/name", "value": "cells"},
+                {"op": "remove", "path": "/ome/multiscales/0/axes/1"},
+                {"op": "add", "path": "/ome/labels", "value": ["nuclei"]},
+            ])),
+        ) else {
+            panic!("expected invalid patch")
+        };
+        assert_eq!(operation, 1);
+
+        let result = apply_json_patch(
+            &attrs,
+            &patch(json!([{"op": "test", "path": "/ome/version", "value": "0.4"}])),
+        );
+        assert!(matches!(result, Err(Error::JsonPatch(_))));
+    }
+
+    #[test]
+    fn merge_patch() {
+        let attrs = attributes();
+        let patched = apply_merge_patch(
+            &attrs,
+            &json!({"ome": {"labels": ["cells"]}, "other": null}),
+        )
+        .unwrap();
+        assert_eq!(
+            patched.ome.labels.as_ref().unwrap(),
+            &vec!["cells".to_string()]
+        );
+        assert!(patched.extensions.0.is_empty());
+
+        let result = apply_merge_patch(&attrs, &json!({"ome": {"multiscales": []}}));
+        assert!(matches!(
+            result,
+            Err(Error::PatchInvalid { operation: 0, .. })
+        ));
+    }
+}
@@ -2,16 +2,19 @@
 //!
 //! <https://ngff.openmicroscopy.org/0.5/#plate-md>.
 
-use std::{collections::HashSet, num::NonZeroU64};
+use std::{
+    collections::{BTreeMap, HashSet},
+    num::NonZeroU64,
+};
 
 use serde::{Deserialize, Serialize};
-use validatrix::Validate;
+use validatrix::{Accumulator, Validate, ValidateContext};
 
-use super::{PlateAcquisition, PlateColumn, PlateRow, PlateWell};
+use super::{PlateAcquisition, PlateColumn, PlateRow, PlateWell, Well};
+use crate::v0_4::plate::validate_plate_wells;
 
 /// `plate` metadata. For high-content screening datasets.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Plate {
     /// A list of JSON objects defining the acquisitions for a given plate to which wells can refer to
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -28,6 +31,9 @@ pub struct Plate {
     pub rows: Vec<PlateRow>,
     /// Defines the wells of the plate.
     pub wells: Vec<PlateWell>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Validate for Plate {
@@ -128,6 +134,26 @@ impl Validate for Plate {
     }
 }
 
+/// Validate the plate along with the `well` metadata of its wells, keyed by well path (e.g. `"A/1"`).
+///
+/// See the v0.4 [`Plate`](crate::v0_4::Plate) for the checks performed.
+impl ValidateContext for Plate {
+    type Context = BTreeMap<String, Well>;
+
+    fn validate_inner(&self, context: &Self::Context, accum: &mut Accumulator) {
+        Validate::validate_inner(self, accum);
+        validate_plate_wells(
+            accum,
+            self.acquisitions.as_deref(),
+            self.field_count,
+            &self.wells,
+            context
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.images.as_slice())),
+        );
+    }
+}
+
 impl From<crate::v0_4::Plate> for Plate {
     fn from(value: crate::v0_4::Plate) -> Self {
         Self {
@@ -137,6 +163,7 @@ impl From<crate::v0_4::Plate> for Plate {
             name: value.name,
             rows: value.rows,
             wells: value.wells,
+            extra: value.extra,
         }
     }
 }
@@ -0,0 +1,233 @@
+//! Navigating the wells of a plate by name, row, and column.
+
+use super::{Plate, PlateWell};
+
+/// Zero-based position of a well in a plate, in the conventional layout
+/// where rows are named with letters and columns with 1-based numbers (e.g. `"C07"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WellPosition {
+    /// Zero-based row index.
+    pub row: usize,
+    /// Zero-based column index.
+    pub column: usize,
+}
+
+impl WellPosition {
+    /// Parse a conventional well name such as `"C07"`, `"c7"`, or `"AA12"`.
+    ///
+    /// Row letters are case-insensitive: `A` to `Z` are rows 0 to 25, then `AA` is row 26.
+    /// Returns `None` if the name is not letters followed by a positive number.
+    pub fn parse(name: &str) -> Option<Self> {
+        let split = name.find(|c: char| !c.is_ascii_alphabetic())?;
+        let (letters, digits) = name.split_at(split);
+        if letters.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let mut row: usize = 0;
+        for c in letters.bytes() {
+            let value = usize::from(c.to_ascii_uppercase() - b'A') + 1;
+            row = row.checked_mul(26)?.checked_add(value)?;
+        }
+        let column: usize = digits.parse().ok()?;
+        Some(Self {
+            row: row - 1,
+            column: column.checked_sub(1)?,
+        })
+    }
+
+    /// Conventional name of the row, e.g. `"C"` for row 2.
+    ///
+    /// Returns `None` for the largest possible index, which has no name.
+    pub fn row_name(&self) -> Option<String> {
+        let mut letters = Vec::default();
+        let mut remaining = self.row.checked_add(1)?;
+        while remaining > 0 {
+            remaining -= 1;
+            letters.push(b'A' + (remaining % 26) as u8);
+            remaining /= 26;
+        }
+        Some(letters.iter().rev().map(|b| char::from(*b)).collect())
+    }
+
+    /// Conventional well name, with the 1-based column number zero-padded to the given width.
+    ///
+    /// For example, row 2 and column 6 is `"C07"` with a width of 2,
+    /// as for 96- and 384-well plates.
+    /// Returns `None` if the row or column is the largest possible index, which has no name.
+    pub fn format(&self, column_width: usize) -> Option<String> {
+        let column = self.column.checked_add(1)?;
+        Some(format!("{}{column:0column_width$}", self.row_name()?))
+    }
+}
+
+impl Plate {
+    /// Name of the well at the given row and column indices,
+    /// formed by concatenating the plate's row and column names (e.g. `"C07"`).
+    ///
+    /// Returns `None` if either index is out of bounds.
+    pub fn well_name(&self, row_index: usize, column_index: usize) -> Option<String> {
+        let row = self.rows.get(row_index)?;
+        let column = self.columns.get(column_index)?;
+        Some(format!("{}{}", row.name, column.name))
+    }
+
+    /// Row and column indices of the given well name.
+    ///
+    /// The name may be a row name followed by a column name (e.g. `"C07"`),
+    /// or a well path (e.g. `"C/07"`).
+    /// Numeric column names match regardless of zero-padding, so `"C7"` matches column `"07"`.
+    pub fn parse_well_name(&self, name: &str) -> Option<(usize, usize)> {
+        let column_matches = |candidate: &str, column: &str| {
+            candidate == column
+                || matches!(
+                    (candidate.parse::<u64>(), column.parse::<u64>()),
+                    (Ok(a), Ok(b)) if a == b
+                )
+        };
+        let find_column = |candidate: &str| {
+            self.columns
+                .iter()
+                .position(|c| c.name == candidate)
+                .or_else(|| {
+                    self.columns
+                        .iter()
+                        .position(|c| column_matches(candidate, &c.name))
+                })
+        };
+
+        if let Some((row, column)) = name.split_once('/') {
+            let row_index = self.rows.iter().position(|r| r.name == row)?;
+            return Some((row_index, find_column(column)?));
+        }
+
+        // prefer the longest matching row name, in case one is a prefix of another
+        let mut candidates: Vec<_> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| name.starts_with(r.name.as_str()))
+            .collect();
+        candidates.sort_by_key(|(_, r)| std::cmp::Reverse(r.name.len()));
+        candidates.into_iter().find_map(|(row_index, r)| {
+            let column_index = find_column(&name[r.name.len()..])?;
+            Some((row_index, column_index))
+        })
+    }
+
+    /// The well with the given name (e.g. `"C07"` or `"C/07"`), if it is present in the plate.
+    ///
+    /// See [Plate::parse_well_name].
+    pub fn well(&self, name: &str) -> Option<&PlateWell> {
+        let (row_index, column_index) = self.parse_well_name(name)?;
+        self.well_at(row_index, column_index)
+    }
+
+    /// The well at the given row and column indices, if it is present in the plate.
+    pub fn well_at(&self, row_index: usize, column_index: usize) -> Option<&PlateWell> {
+        self.wells
+            .iter()
+            .find(|w| w.row_index == row_index && w.column_index == column_index)
+    }
+
+    /// Wells present in the given row, in column order.
+    pub fn row_wells(&self, row_index: usize) -> impl Iterator<Item = &PlateWell> {
+        let mut wells: Vec<_> = self
+            .wells
+            .iter()
+            .filter(|w| w.row_index == row_index)
+            .collect();
+        wells.sort_by_key(|w| w.column_index);
+        wells.into_iter()
+    }
+
+    /// Wells present in the given column, in row order.
+    pub fn column_wells(&self, column_index: usize) -> impl Iterator<Item = &PlateWell> {
+        let mut wells: Vec<_> = self
+            .wells
+            .iter()
+            .filter(|w| w.column_index == column_index)
+            .collect();
+        wells.sort_by_key(|w| w.row_index);
+        wells.into_iter()
+    }
+
+    /// Dense grid of wells indexed by `[row_index][column_index]`,
+    /// with `None` for positions which have no well.
+    ///
+    /// Wells whose indices are out of bounds are omitted.
+    pub fn grid(&self) -> Vec<Vec<Option<&PlateWell>>> {
+        let mut grid = vec![vec![None; self.columns.len()]; self.rows.len()];
+        for well in self.wells.iter() {
+            if let Some(cell) = grid
+                .get_mut(well.row_index)
+                .and_then(|row| row.get_mut(well.column_index))
+            {
+                cell.get_or_insert(well);
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn well_position() {
+        let pos = WellPosition::parse("C07").unwrap();
+        assert_eq!(pos, WellPosition { row: 2, column: 6 });
+        assert_eq!(pos.format(2).as_deref(), Some("C07"));
+        assert_eq!(WellPosition::parse("c7"), Some(pos));
+        let pos = WellPosition::parse("AA1").unwrap();
+        assert_eq!(pos.row, 26);
+        assert_eq!(pos.format(1).as_deref(), Some("AA1"));
+        assert_eq!(WellPosition::parse("C0"), None);
+        assert_eq!(WellPosition::parse("7"), None);
+        assert_eq!(WellPosition::parse("C"), None);
+    }
+
+    #[test]
+    fn well_position_upper_bound() {
+        let last = WellPosition {
+            row: usize::MAX - 1,
+            column: usize::MAX - 1,
+        };
+        let name = last.format(1).unwrap();
+        assert_eq!(WellPosition::parse(&name), Some(last));
+        let beyond = WellPosition {
+            row: usize::MAX,
+            column: 0,
+        };
+        assert_eq!(beyond.row_name(), None);
+        assert_eq!(beyond.format(2), None);
+        let beyond = WellPosition {
+            row: 0,
+            column: usize::MAX,
+        };
+        assert_eq!(beyond.format(2), None);
+        assert_eq!(WellPosition::parse(&"Z".repeat(20)), None);
+    }
+
+    #[test]
+    fn plate_navigation() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/ome-zarr/specifications/0.5/examples/plate_strict/plate_6wells.json"
+        ));
+        let meta: crate::v0_5::OmeZarrGroupMetadata = serde_json::from_str(json).unwrap();
+        let plate = meta.attributes.ome.plate.unwrap();
+
+        assert_eq!(plate.well_name(0, 0).as_deref(), Some("A1"));
+        let well = plate.well("B3").unwrap();
+        assert_eq!((well.row_index, well.column_index), (1, 2));
+        assert!(plate.well("B/03").is_some());
+        assert!(plate.well("Z1").is_none());
+
+        let grid = plate.grid();
+        assert_eq!(grid.len(), plate.rows.len());
+        let n_wells = grid.iter().flatten().filter(|w| w.is_some()).count();
+        assert_eq!(n_wells, plate.wells.len());
+        assert_eq!(plate.row_wells(0).count(), 3);
+    }
+}
@@ -0,0 +1,310 @@
+//! Rendering a plate layout as an ASCII or SVG plate map.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use super::{Palette, Plate, Well};
+use crate::xml::escape_xml;
+
+/// Summary of a single well position in a [PlateMap].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WellSummary {
+    /// No well at this position.
+    Empty,
+    /// The well is listed in the plate, but no `well` metadata was given for it.
+    Missing {
+        /// Path of the well.
+        path: String,
+    },
+    /// The well is listed in the plate and has `well` metadata.
+    Populated {
+        /// Path of the well.
+        path: String,
+        /// Number of fields of view.
+        fields: usize,
+        /// IDs of the acquisitions which the fields of view belong to.
+        acquisitions: BTreeSet<u64>,
+    },
+}
+
+/// Layout of a plate's wells for quality control, built from a [Plate]
+/// and the `well` metadata of its wells, keyed by well path (e.g. `"A/1"`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlateMap {
+    /// Row names, in order.
+    pub rows: Vec<String>,
+    /// Column names, in order.
+    pub columns: Vec<String>,
+    /// Summary of each well position, indexed by `[row_index][column_index]`.
+    pub cells: Vec<Vec<WellSummary>>,
+    /// IDs of all acquisitions defined in the plate or referenced by its wells, in order.
+    pub acquisitions: Vec<u64>,
+}
+
+/// Size of a well in the SVG plate map, in pixels.
+const SVG_CELL: usize = 40;
+/// Size of the row and column headers in the SVG plate map, in pixels.
+const SVG_HEADER: usize = 30;
+/// Fill color for wells whose fields of view come from several acquisitions.
+const SVG_MIXED_FILL: &str = "#9e9e9e";
+
+impl PlateMap {
+    /// Summarise the wells of a plate.
+    pub fn new(plate: &Plate, wells: &BTreeMap<String, Well>) -> Self {
+        let mut acquisitions: BTreeSet<u64> =
+            plate.acquisitions.iter().flatten().map(|a| a.id).collect();
+        let cells = plate
+            .grid()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|well| {
+                        let Some(well) = well else {
+                            return WellSummary::Empty;
+                        };
+                        let path = well.path.to_string_lossy().into_owned();
+                        let Some(meta) = wells.get(&path) else {
+                            return WellSummary::Missing { path };
+                        };
+                        let well_acquisitions: BTreeSet<u64> =
+                            meta.images.iter().filter_map(|i| i.acquisition).collect();
+                        acquisitions.extend(well_acquisitions.iter().copied());
+                        WellSummary::Populated {
+                            path,
+                            fields: meta.images.len(),
+                            acquisitions: well_acquisitions,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            rows: plate.rows.iter().map(|r| r.name.clone()).collect(),
+            columns: plate.columns.iter().map(|c| c.name.clone()).collect(),
+            cells,
+            acquisitions: acquisitions.into_iter().collect(),
+        }
+    }
+
+    /// Render as a plain-text grid.
+    ///
+    /// Each populated well shows its number of fields of view,
+    /// followed by its acquisition IDs if it has any (e.g. `2:1,2`).
+    /// Empty positions are shown as `.`, and wells without metadata as `?`.
+    pub fn to_ascii(&self) -> String {
+        let text: Vec<Vec<String>> = self
+            .cells
+            .iter()
+            .map(|row| row.iter().map(ascii_cell).collect())
+            .collect();
+        let width = text
+            .iter()
+            .flatten()
+            .chain(self.columns.iter())
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(1);
+        let header_width = self
+            .rows
+            .iter()
+            .map(|r| r.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut out = format!("{:header_width$}", "");
+        for column in self.columns.iter() {
+            write!(out, " {column:>width$}").unwrap();
+        }
+        out.push('\n');
+        for (row, cells) in self.rows.iter().zip(text.iter()) {
+            write!(out, "{row:<header_width$}").unwrap();
+            for cell in cells {
+                write!(out, " {cell:>width$}").unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Render as a standalone SVG document.
+    ///
+    /// Populated wells are filled with a color per acquisition (grey if several)
+    /// and labelled with their number of fields of view;
+    /// wells without metadata are dashed, and empty positions are blank.
+    /// Each well has a tooltip with its path, fields of view, and acquisitions.
+    pub fn to_svg(&self) -> String {
+        let colors: BTreeMap<u64, String> = Palette::GoldenRatio
+            .generate(self.acquisitions.iter().copied(), 0)
+            .into_iter()
+            .map(|c| {
+                let [r, g, b, _] = c.rgba;
+                (c.label_value, format!("#{r:02x}{g:02x}{b:02x}"))
+            })
+            .collect();
+        let legend_height = if self.acquisitions.is_empty() {
+            0
+        } else {
+            SVG_HEADER
+        };
+        // each legend entry takes the width of two wells
+        let width = SVG_HEADER + SVG_CELL * self.columns.len().max(2 * colors.len());
+        let height = SVG_HEADER + SVG_CELL * self.rows.len() + legend_height;
+        let radius = SVG_CELL * 2 / 5;
+
+        let mut out = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12">"#
+        );
+        out.push('\n');
+        for (idx, column) in self.columns.iter().enumerate() {
+            let x = SVG_HEADER + SVG_CELL * idx + SVG_CELL / 2;
+            writeln!(
+                out,
+                r#"<text x="{x}" y="{}" text-anchor="middle">{}</text>"#,
+                SVG_HEADER * 2 / 3,
+                escape_xml(column)
+            )
+            .unwrap();
+        }
+        for (row_idx, (row, cells)) in self.rows.iter().zip(self.cells.iter()).enumerate() {
+            let cy = SVG_HEADER + SVG_CELL * row_idx + SVG_CELL / 2;
+            writeln!(
+                out,
+                r#"<text x="{}" y="{cy}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                SVG_HEADER / 2,
+                escape_xml(row)
+            )
+            .unwrap();
+            for (col_idx, cell) in cells.iter().enumerate() {
+                let cx = SVG_HEADER + SVG_CELL * col_idx + SVG_CELL / 2;
+                let circle = format!(r#"<circle cx="{cx}" cy="{cy}" r="{radius}""#);
+                match cell {
+                    WellSummary::Empty => {
+                        writeln!(out, r##"{circle} fill="none" stroke="#dddddd"/>"##).unwrap();
+                    }
+                    WellSummary::Missing { path } => {
+                        writeln!(
+                            out,
+                            r##"<g>{circle} fill="none" stroke="#888888" stroke-dasharray="3,3"/><title>{}: no well metadata</title></g>"##,
+                            escape_xml(path)
+                        )
+                        .unwrap();
+                    }
+                    WellSummary::Populated {
+                        path,
+                        fields,
+                        acquisitions,
+                    } => {
+                        let fill = match acquisitions.len() {
+                            0 => "#ffffff",
+                            1 => acquisitions
+                                .first()
+                                .and_then(|a| colors.get(a))
+                                .map_or(SVG_MIXED_FILL, String::as_str),
+                            _ => SVG_MIXED_FILL,
+                        };
+                        let ids = join_ids(acquisitions);
+                        writeln!(
+                            out,
+                            r##"<g>{circle} fill="{fill}" stroke="#333333"/><text x="{cx}" y="{cy}" text-anchor="middle" dominant-baseline="central">{fields}</text><title>{}: {fields} fields; acquisitions: {}</title></g>"##,
+                            escape_xml(path),
+                            if ids.is_empty() { "none" } else { &ids },
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+        let legend_y = SVG_HEADER + SVG_CELL * self.rows.len() + SVG_HEADER / 2;
+        for (idx, (id, color)) in colors.iter().enumerate() {
+            let x = SVG_HEADER + SVG_CELL * 2 * idx;
+            writeln!(
+                out,
+                r##"<rect x="{x}" y="{}" width="10" height="10" fill="{color}" stroke="#333333"/><text x="{}" y="{legend_y}" dominant-baseline="central">acq. {id}</text>"##,
+                legend_y - 5,
+                x + 14,
+            )
+            .unwrap();
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+fn ascii_cell(cell: &WellSummary) -> String {
+    match cell {
+        WellSummary::Empty => ".".into(),
+        WellSummary::Missing { .. } => "?".into(),
+        WellSummary::Populated {
+            fields,
+            acquisitions,
+            ..
+        } if acquisitions.is_empty() => fields.to_string(),
+        WellSummary::Populated {
+            fields,
+            acquisitions,
+            ..
+        } => format!("{fields}:{}", join_ids(acquisitions)),
+    }
+}
+
+fn join_ids(ids: &BTreeSet<u64>) -> String {
+    ids.iter().map(u64::to_string).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plate_map() -> PlateMap {
+        let plate: Plate = serde_json::from_value(serde_json::json!({
+            "acquisitions": [{"id": 1}, {"id": 2}],
+            "columns": [{"name": "1"}, {"name": "2"}, {"name": "3"}],
+            "rows": [{"name": "A"}, {"name": "B"}],
+            "wells": [
+                {"path": "A/1", "rowIndex": 0, "columnIndex": 0},
+                {"path": "A/2", "rowIndex": 0, "columnIndex": 1},
+                {"path": "B/3", "rowIndex": 1, "columnIndex": 2}
+            ]
+        }))
+        .unwrap();
+        let well = |images: serde_json::Value| -> Well {
+            serde_json::from_value(serde_json::json!({ "images": images })).unwrap()
+        };
+        let wells = BTreeMap::from([
+            (
+                "A/1".to_string(),
+                well(serde_json::json!([
+                    {"path": "0", "acquisition": 1},
+                    {"path": "1", "acquisition": 2}
+                ])),
+            ),
+            (
+                "B/3".to_string(),
+                well(serde_json::json!([{"path": "0", "acquisition": 2}])),
+            ),
+        ]);
+        PlateMap::new(&plate, &wells)
+    }
+
+    #[test]
+    fn ascii() {
+        let expected = concat!(
+            "      1     2     3\n",
+            "A 2:1,2     ?     .\n",
+            "B     .     .   1:2\n",
+        );
+        assert_eq!(plate_map().to_ascii(), expected);
+    }
+
+    #[test]
+    fn svg() {
+        let svg = plate_map().to_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<circle").count(), 6);
+        assert!(svg.contains("<title>A/1: 2 fields; acquisitions: 1,2</title>"));
+        assert!(svg.contains("acq. 2"));
+    }
+}
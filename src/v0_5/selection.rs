@@ -0,0 +1,206 @@
+//! Choosing a resolution level of a multiscale image.
+
+use crate::{Error, Result};
+
+use super::{AxisType, MultiscaleImage};
+
+/// Relative tolerance used when comparing a level's scale against a target.
+const RELATIVE_TOLERANCE: f64 = 1e-9;
+
+/// Policy for choosing a resolution level given a target physical voxel size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LevelPolicy {
+    /// The level whose scale is closest to the target, compared on a logarithmic scale.
+    ///
+    /// Ties are broken in favour of the finer level.
+    Closest,
+    /// The finest level whose scale is at least the target.
+    ///
+    /// Useful for analysis jobs which must not read more data than necessary.
+    FinestCoarserThan,
+    /// The coarsest level whose scale is at most the target.
+    ///
+    /// Useful for viewers which must not under-sample the screen.
+    CoarsestFinerThan,
+}
+
+/// Effective scale of a single resolution level on some chosen axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelScale {
+    /// Index of the level in the multiscale image's `datasets`.
+    pub index: usize,
+    /// Geometric mean of the level's scale over the chosen axes.
+    pub scale: f64,
+}
+
+impl MultiscaleImage {
+    /// Index of the axis with the given name.
+    pub fn axis_index(&self, name: &str) -> Option<usize> {
+        self.axes.iter().position(|a| a.name == name)
+    }
+
+    /// Indices of the axes with type `space`.
+    pub fn space_axis_indices(&self) -> Vec<usize> {
+        self.axes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, a)| (a.r#type == Some(AxisType::Space)).then_some(idx))
+            .collect()
+    }
+
+    /// Rank the resolution levels by their effective scale on the given axes, finest first.
+    ///
+    /// The effective scale is the geometric mean of the scale of each given axis,
+    /// combining dataset-level and multiscale-level transformations.
+    ///
+    /// # Errors
+    /// Returns an error if no axes are given, if any axis index is out of bounds,
+    /// or if the transformations cannot be resolved (see [MultiscaleImage::level_transforms]).
+    pub fn rank_levels(&self, axes: &[usize]) -> Result<Vec<LevelScale>> {
+        if axes.is_empty() {
+            return Err(Error::general("no axes given for ranking levels"));
+        }
+        if let Some(idx) = axes.iter().find(|idx| **idx >= self.axes.len()) {
+            return Err(Error::general(format!(
+                "axis index {idx} out of bounds for {} axes",
+                self.axes.len()
+            )));
+        }
+        let mut ranked = self
+            .level_transforms()?
+            .into_iter()
+            .enumerate()
+            .map(|(index, tf)| {
+                let log_sum: f64 = axes.iter().map(|idx| tf.scale[*idx].abs().ln()).sum();
+                LevelScale {
+                    index,
+                    scale: (log_sum / axes.len() as f64).exp(),
+                }
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| a.scale.total_cmp(&b.scale).then(a.index.cmp(&b.index)));
+        Ok(ranked)
+    }
+
+    /// Choose the resolution level for a target physical voxel size on the given axes.
+    ///
+    /// Returns the index of the level in `datasets`,
+    /// or `None` if no level satisfies the policy.
+    ///
+    /// # Errors
+    /// See [MultiscaleImage::rank_levels].
+    pub fn select_level(
+        &self,
+        target: f64,
+        axes: &[usize],
+        policy: LevelPolicy,
+    ) -> Result<Option<usize>> {
+        let ranked = self.rank_levels(axes)?;
+        let tolerance = target.abs() * RELATIVE_TOLERANCE;
+        let selected = match policy {
+            LevelPolicy::Closest => ranked.iter().min_by(|a, b| {
+                let da = (a.scale / target).ln().abs();
+                let db = (b.scale / target).ln().abs();
+                da.total_cmp(&db)
+            }),
+            LevelPolicy::FinestCoarserThan => ranked.iter().find(|l| l.scale >= target - tolerance),
+            LevelPolicy::CoarsestFinerThan => {
+                ranked.iter().rev().find(|l| l.scale <= target + tolerance)
+            }
+        };
+        Ok(selected.map(|l| l.index))
+    }
+
+    /// Choose the resolution level for displaying a physical region in a viewport.
+    ///
+    /// `region` is the physical size of the region along each of the given axes,
+    /// and `viewport` is the number of screen pixels available along each of those axes.
+    /// The target voxel size is the geometric mean of `region / viewport` over the axes.
+    ///
+    /// # Errors
+    /// Returns an error if `region` or `viewport` do not match the number of axes,
+    /// or see [MultiscaleImage::rank_levels].
+    pub fn select_level_for_viewport(
+        &self,
+        axes: &[usize],
+        region: &[f64],
+        viewport: &[u64],
+        policy: LevelPolicy,
+    ) -> Result<Option<usize>> {
+        for len in [region.len(), viewport.len()] {
+            if len != axes.len() {
+                return Err(Error::NDimMismatch {
+                    expected: axes.len(),
+                    actual: len,
+                });
+            }
+        }
+        let log_sum: f64 = region
+            .iter()
+            .zip(viewport)
+            .map(|(r, v)| (r.abs() / *v as f64).ln())
+            .sum();
+        let target = (log_sum / axes.len() as f64).exp();
+        self.select_level(target, axes, policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> MultiscaleImage {
+        serde_json::from_str(
+            r#"{
+                "axes": [
+                    {"name": "c", "type": "channel"},
+                    {"name": "y", "type": "space", "unit": "micrometer"},
+                    {"name": "x", "type": "space", "unit": "micrometer"}
+                ],
+                "datasets": [
+                    {"path": "0", "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 0.5, 0.5]}
+                    ]},
+                    {"path": "1", "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 1.0, 1.0]}
+                    ]},
+                    {"path": "2", "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 2.0, 2.0]}
+                    ]}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn select_level() {
+        let img = image();
+        let axes = img.space_axis_indices();
+        assert_eq!(axes, vec![1, 2]);
+        let select = |target, policy| img.select_level(target, &axes, policy).unwrap();
+
+        assert_eq!(select(0.8, LevelPolicy::Closest), Some(1));
+        assert_eq!(select(0.6, LevelPolicy::Closest), Some(0));
+        assert_eq!(select(0.8, LevelPolicy::FinestCoarserThan), Some(1));
+        assert_eq!(select(1.0, LevelPolicy::FinestCoarserThan), Some(1));
+        assert_eq!(select(3.0, LevelPolicy::FinestCoarserThan), None);
+        assert_eq!(select(1.5, LevelPolicy::CoarsestFinerThan), Some(1));
+        assert_eq!(select(0.1, LevelPolicy::CoarsestFinerThan), None);
+    }
+
+    #[test]
+    fn select_level_for_viewport() {
+        let img = image();
+        let axes = [img.axis_index("y").unwrap(), img.axis_index("x").unwrap()];
+        let level = img
+            .select_level_for_viewport(
+                &axes,
+                &[1024.0, 1024.0],
+                &[512, 512],
+                LevelPolicy::CoarsestFinerThan,
+            )
+            .unwrap();
+        assert_eq!(level, Some(2));
+    }
+}
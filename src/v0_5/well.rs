@@ -3,21 +3,32 @@
 //! <https://ngff.openmicroscopy.org/0.5/#well-md>.
 
 use serde::{Deserialize, Serialize};
+use validatrix::{Accumulator, Validate};
 
 use super::WellImage;
+use crate::v0_4::well::validate_well_images;
 
 /// `well` metadata. Describes all fields of views under a given well.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Well {
     /// Specifies the fields of views of the well.
     pub images: Vec<WellImage>,
+    /// Catch-all field for any fields not specified in OME-Zarr.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Validate for Well {
+    fn validate_inner(&self, accum: &mut Accumulator) {
+        accum.with_key("images", |a| validate_well_images(a, &self.images));
+    }
 }
 
 impl From<crate::v0_4::Well> for Well {
     fn from(value: crate::v0_4::Well) -> Self {
         Self {
             images: value.images,
+            extra: value.extra,
         }
     }
 }
@@ -0,0 +1,213 @@
+use std::num::NonZeroU64;
+
+use crate::{
+    v0_4,
+    v0_4::{
+        Axis, CoordinateTransform, ImageLabelColor, ImageLabelProperties, ImageLabelSource,
+        MultiscaleImageDataset, MultiscaleImageMetadata, PlateAcquisition, PlateColumn, PlateRow,
+        PlateWell, WellImage,
+    },
+    v0_5,
+};
+
+/// Read-only view of a multiscale image, in any supported version.
+pub trait MultiscaleImageView {
+    /// Name of the multiscale image.
+    fn name(&self) -> Option<&str>;
+
+    /// Axes of the multiscale image.
+    fn axes(&self) -> &[Axis];
+
+    /// Datasets (resolution levels) of the multiscale image, from highest to lowest resolution.
+    fn datasets(&self) -> &[MultiscaleImageDataset];
+
+    /// Transformations applied to every dataset.
+    fn coordinate_transformations(&self) -> Option<&[CoordinateTransform]>;
+
+    /// Type of downscaling method used to generate the multiscale image.
+    fn r#type(&self) -> Option<&str>;
+
+    /// Unstructured information about the downscaling method.
+    fn metadata(&self) -> Option<&MultiscaleImageMetadata>;
+}
+
+macro_rules! impl_multiscale_image_view {
+    ($($t:ty),*) => {$(
+        impl MultiscaleImageView for $t {
+            fn name(&self) -> Option<&str> {
+                self.name.as_deref()
+            }
+
+            fn axes(&self) -> &[Axis] {
+                &self.axes
+            }
+
+            fn datasets(&self) -> &[MultiscaleImageDataset] {
+                &self.datasets
+            }
+
+            fn coordinate_transformations(&self) -> Option<&[CoordinateTransform]> {
+                self.coordinate_transformations.as_deref()
+            }
+
+            fn r#type(&self) -> Option<&str> {
+                self.r#type.as_deref()
+            }
+
+            fn metadata(&self) -> Option<&MultiscaleImageMetadata> {
+                self.metadata.as_ref()
+            }
+        }
+    )*};
+}
+
+impl_multiscale_image_view!(v0_4::MultiscaleImage, v0_5::MultiscaleImage);
+
+/// Read-only view of plate metadata, in any supported version.
+pub trait PlateView {
+    /// Name of the plate.
+    fn name(&self) -> Option<&str>;
+
+    /// Acquisitions of the plate; empty if none are listed.
+    fn acquisitions(&self) -> &[PlateAcquisition];
+
+    /// Columns of the plate.
+    fn columns(&self) -> &[PlateColumn];
+
+    /// Rows of the plate.
+    fn rows(&self) -> &[PlateRow];
+
+    /// Wells of the plate.
+    fn wells(&self) -> &[PlateWell];
+
+    /// Maximum number of fields per view across all wells.
+    fn field_count(&self) -> Option<NonZeroU64>;
+}
+
+macro_rules! impl_plate_view {
+    ($($t:ty),*) => {$(
+        impl PlateView for $t {
+            fn name(&self) -> Option<&str> {
+                self.name.as_deref()
+            }
+
+            fn acquisitions(&self) -> &[PlateAcquisition] {
+                self.acquisitions.as_deref().unwrap_or_default()
+            }
+
+            fn columns(&self) -> &[PlateColumn] {
+                &self.columns
+            }
+
+            fn rows(&self) -> &[PlateRow] {
+                &self.rows
+            }
+
+            fn wells(&self) -> &[PlateWell] {
+                &self.wells
+            }
+
+            fn field_count(&self) -> Option<NonZeroU64> {
+                self.field_count
+            }
+        }
+    )*};
+}
+
+impl_plate_view!(v0_4::Plate, v0_5::Plate);
+
+/// Read-only view of well metadata, in any supported version.
+pub trait WellView {
+    /// Fields of view in the well.
+    fn images(&self) -> &[WellImage];
+}
+
+macro_rules! impl_well_view {
+    ($($t:ty),*) => {$(
+        impl WellView for $t {
+            fn images(&self) -> &[WellImage] {
+                &self.images
+            }
+        }
+    )*};
+}
+
+impl_well_view!(v0_4::Well, v0_5::Well);
+
+/// Read-only view of label image metadata, in any supported version.
+pub trait ImageLabelView {
+    /// Display colors of label values; empty if none are listed.
+    fn colors(&self) -> &[ImageLabelColor];
+
+    /// Arbitrary properties of label values; empty if none are listed.
+    fn properties(&self) -> &[ImageLabelProperties];
+
+    /// Image from which the label image was derived.
+    fn source(&self) -> Option<&ImageLabelSource>;
+}
+
+macro_rules! impl_image_label_view {
+    ($($t:ty),*) => {$(
+        impl ImageLabelView for $t {
+            fn colors(&self) -> &[ImageLabelColor] {
+                self.colors.as_deref().unwrap_or_default()
+            }
+
+            fn properties(&self) -> &[ImageLabelProperties] {
+                self.properties.as_deref().unwrap_or_default()
+            }
+
+            fn source(&self) -> Option<&ImageLabelSource> {
+                self.source.as_ref()
+            }
+        }
+    )*};
+}
+
+impl_image_label_view!(v0_4::ImageLabel, v0_5::ImageLabel);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::AnyOmeFields;
+
+    #[test]
+    fn views_across_versions() {
+        let multiscale = json!({
+            "name": "cells",
+            "axes": [{"name": "y", "type": "space"}, {"name": "x", "type": "space"}],
+            "datasets": [{"path": "0", "coordinateTransformations": [
+                {"type": "scale", "scale": [0.5, 0.5]}
+            ]}]
+        });
+        let mut multiscale_v0_4 = multiscale.clone();
+        multiscale_v0_4["version"] = "0.4".into();
+        let label = json!({"colors": [{"label-value": 1, "rgba": [255, 0, 0, 255]}]});
+        let mut label_v0_4 = label.clone();
+        label_v0_4["version"] = "0.4".into();
+
+        let all: Vec<AnyOmeFields> = [
+            json!({"multiscales": [multiscale_v0_4], "image-label": label_v0_4}),
+            json!({"ome": {"version": "0.5", "multiscales": [multiscale], "image-label": label}}),
+        ]
+        .into_iter()
+        .map(|v| serde_json::from_value(v).unwrap())
+        .collect();
+
+        for fields in all {
+            let ms = fields.multiscales();
+            assert_eq!(ms.len(), 1);
+            assert_eq!(ms[0].name(), Some("cells"));
+            assert_eq!(ms[0].axes()[1].name, "x");
+            assert_eq!(ms[0].datasets()[0].path, "0");
+            assert!(ms[0].coordinate_transformations().is_none());
+
+            let label = fields.image_label().unwrap();
+            assert_eq!(label.colors()[0].label_value, 1);
+            assert!(label.properties().is_empty());
+            assert!(fields.plate().is_none());
+            assert!(fields.well().is_none());
+        }
+    }
+}
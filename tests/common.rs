@@ -1,7 +1,8 @@
 use std::io::Read;
 
 use ome_zarr_metadata::{Valid, Validate};
-use serde::{Deserialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
 
 #[allow(unused)]
 #[derive(Debug, Deserialize)]
@@ -55,3 +56,20 @@ pub fn test_upgrade<Src: DeserializeOwned + Validate, Tgt: From<Src> + Validate>
     let upper = Tgt::from(lower);
     upper.validate().unwrap();
 }
+
+/// Represent all numbers as floats, so that e.g. `1` and `1.0` compare equal.
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Number(n) => n.as_f64().map_or(Value::Number(n), Value::from),
+        Value::Array(a) => a.into_iter().map(normalize).collect(),
+        Value::Object(o) => Value::Object(o.into_iter().map(|(k, v)| (k, normalize(v))).collect()),
+        v => v,
+    }
+}
+
+pub fn test_roundtrip<T: DeserializeOwned + Serialize>(bytes: &[u8]) {
+    let expected: Value = serde_json::from_slice(&strip_comments(bytes)).unwrap();
+    let parsed: T = serde_json::from_value(expected.clone()).unwrap();
+    let actual: Value = serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+    assert_eq!(normalize(actual), normalize(expected));
+}
@@ -1,5 +1,5 @@
 mod common;
-use common::{test_case, test_example};
+use common::{test_case, test_example, test_roundtrip};
 use ome_zarr_metadata::v0_4;
 use rstest::rstest;
 
@@ -32,3 +32,14 @@ fn v04_examples(
 ) {
     test_example::<v0_4::OmeNgffGroupAttributes>(bytes);
 }
+
+#[rstest]
+fn v04_roundtrip(
+    #[files("**/*.json")]
+    #[base_dir = "ome-zarr/specifications/0.4/examples"]
+    #[exclude("ome/series-2")]
+    #[mode = bytes]
+    bytes: &[u8],
+) {
+    test_roundtrip::<v0_4::OmeNgffGroupAttributes>(bytes);
+}
@@ -1,5 +1,5 @@
 mod common;
-use common::{test_case, test_example};
+use common::{test_case, test_example, test_roundtrip};
 use ome_zarr_metadata::{v0_4, v0_5};
 use rstest::rstest;
 
@@ -34,6 +34,17 @@ fn v05_examples(
     test_example::<v0_5::OmeZarrGroupMetadata>(bytes);
 }
 
+#[rstest]
+fn v05_roundtrip(
+    #[files("**/*.json")]
+    #[base_dir = "ome-zarr/specifications/0.5/examples"]
+    #[exclude("ome/series-2")]
+    #[mode = bytes]
+    bytes: &[u8],
+) {
+    test_roundtrip::<v0_5::OmeZarrGroupMetadata>(bytes);
+}
+
 #[rstest]
 fn v04_to_v05(
     #[files("**/*.json")]